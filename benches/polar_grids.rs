@@ -1,10 +1,13 @@
 #[macro_use]
 extern crate criterion;
 extern crate meanderer;
+extern crate rand;
 
 use criterion::{Criterion, ParameterizedBenchmark};
-use meanderer::algorithms::{aldous_broder, growing_tree, hunt_and_kill, iterative_backtracker, last_selection, mixed_selection, random_selection, recursive_backtracker, simplified_prims, true_prims, wilsons};
-use meanderer::data::PolarGrid;
+use meanderer::algorithms::{aldous_broder, growing_tree, hunt_and_kill, iterative_backtracker, kruskals, last_selection, mixed_selection, random_selection, recursive_backtracker, simplified_prims, true_prims, wilsons};
+use meanderer::algorithms::braid;
+use meanderer::data::{MazeGrid, PolarGrid, Position};
+use meanderer::solver::{astar, polar_distance, solve};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let parameters = vec![10, 20, 30, 40];
@@ -12,49 +15,115 @@ fn criterion_benchmark(c: &mut Criterion) {
         "aldous-broder",
         |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| aldous_broder(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                aldous_broder(&mut grid, &mut rand::thread_rng())
+            })
         },
         parameters,
     )
         .with_function("wilsons", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| wilsons(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                wilsons(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("hunt-and-kill", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| hunt_and_kill(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                hunt_and_kill(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("recursive-backtracker", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| recursive_backtracker(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                recursive_backtracker(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("iterative-backtracker", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| iterative_backtracker(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                iterative_backtracker(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("simplified-prims", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| simplified_prims(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                simplified_prims(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("true-prims", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| true_prims(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                true_prims(&mut grid, &mut rand::thread_rng())
+            })
+        })
+        .with_function("kruskals", |b, i| {
+            let mut grid = PolarGrid::new(*i);
+            b.iter(|| {
+                grid.reset_links();
+                kruskals(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("growing-tree (last)", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| growing_tree(&mut grid, last_selection::<PolarGrid>))
+            b.iter(|| {
+                grid.reset_links();
+                growing_tree(&mut grid, last_selection::<PolarGrid, _>, &mut rand::thread_rng())
+            })
         })
         .with_function("growing-tree (random)", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| growing_tree(&mut grid, random_selection::<PolarGrid>))
+            b.iter(|| {
+                grid.reset_links();
+                growing_tree(&mut grid, random_selection::<PolarGrid, _>, &mut rand::thread_rng())
+            })
         })
         .with_function("growing-tree (mixed)", |b, i| {
             let mut grid = PolarGrid::new(*i);
-            b.iter(|| growing_tree(&mut grid, mixed_selection::<PolarGrid>))
+            b.iter(|| {
+                grid.reset_links();
+                growing_tree(&mut grid, mixed_selection::<PolarGrid, _>, &mut rand::thread_rng())
+            })
         });
 
     c.bench("Maze algorithms for N-row polar grids", polar_benchmark);
 }
 
-criterion_group!(benches, criterion_benchmark);
+// braided so that there is more than one route for the solvers to weigh up
+fn braided(rows: usize) -> PolarGrid {
+    let mut rng = rand::thread_rng();
+    let mut grid = PolarGrid::new(rows);
+    recursive_backtracker(&mut grid, &mut rng);
+    braid(&mut grid, 0.5, &mut rng);
+    grid
+}
+
+fn solver_benchmark(c: &mut Criterion) {
+    let parameters = vec![10, 20, 40];
+    let solver_benchmark = ParameterizedBenchmark::new(
+        "solve",
+        |b, i| {
+            let mut grid = braided(*i);
+            let goal = Position::new(*i - 1, 0);
+            b.iter(|| solve(&mut grid, &Position::new(*i / 2, 0), &goal))
+        },
+        parameters,
+    ).with_function("astar (polar distance)", |b, i| {
+        let grid = braided(*i);
+        let start = Position::new(*i / 2, 0);
+        let goal = Position::new(*i - 1, 0);
+        b.iter(|| astar(&grid, &start, &goal, polar_distance))
+    });
+
+    c.bench("Solvers for braided N-row polar grids", solver_benchmark);
+}
+
+criterion_group!(benches, criterion_benchmark, solver_benchmark);
 criterion_main!(benches);
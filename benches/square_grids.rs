@@ -1,10 +1,13 @@
 #[macro_use]
 extern crate criterion;
 extern crate meanderer;
+extern crate rand;
 
 use criterion::{Criterion, ParameterizedBenchmark};
-use meanderer::algorithms::{aldous_broder, binary, growing_tree, hunt_and_kill, iterative_backtracker, last_selection, mixed_selection, random_selection, recursive_backtracker, sidewinder, simplified_prims, true_prims, wilsons};
-use meanderer::data::Grid;
+use meanderer::algorithms::{aldous_broder, binary, ellers, growing_tree, hunt_and_kill, iterative_backtracker, kruskals, last_selection, mixed_selection, random_selection, recursive_backtracker, recursive_division, sidewinder, simplified_prims, true_prims, wilsons};
+use meanderer::algorithms::{braid, DivisionOptions};
+use meanderer::data::{Grid, MazeGrid, Position};
+use meanderer::solver::{astar, manhattan, solve};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let parameters = vec![10, 20, 30, 40];
@@ -12,56 +15,141 @@ fn criterion_benchmark(c: &mut Criterion) {
         "binary",
         |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| binary(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                binary(&mut grid, &mut rand::thread_rng())
+            })
         },
         parameters,
     ).with_function("sidewinder", |b, i| {
         let mut grid = Grid::new(*i, *i);
-        b.iter(|| sidewinder(&mut grid))
+        b.iter(|| {
+            grid.reset_links();
+            sidewinder(&mut grid, &mut rand::thread_rng())
+        })
     })
         .with_function("aldous-broder", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| aldous_broder(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                aldous_broder(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("wilsons", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| wilsons(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                wilsons(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("hunt-and-kill", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| hunt_and_kill(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                hunt_and_kill(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("recursive-backtracker", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| recursive_backtracker(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                recursive_backtracker(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("iterative-backtracker", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| iterative_backtracker(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                iterative_backtracker(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("simplified-prims", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| simplified_prims(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                simplified_prims(&mut grid, &mut rand::thread_rng())
+            })
         })
         .with_function("true-prims", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| true_prims(&mut grid))
+            b.iter(|| {
+                grid.reset_links();
+                true_prims(&mut grid, &mut rand::thread_rng())
+            })
+        })
+        .with_function("kruskals", |b, i| {
+            let mut grid = Grid::new(*i, *i);
+            b.iter(|| {
+                grid.reset_links();
+                kruskals(&mut grid, &mut rand::thread_rng())
+            })
+        })
+        .with_function("ellers", |b, i| {
+            let mut grid = Grid::new(*i, *i);
+            b.iter(|| {
+                grid.reset_links();
+                ellers(&mut grid, &mut rand::thread_rng())
+            })
+        })
+        .with_function("recursive-division", |b, i| {
+            let mut grid = Grid::new(*i, *i);
+            b.iter(|| {
+                grid.reset_links();
+                recursive_division(&mut grid, &DivisionOptions::default(), &mut rand::thread_rng())
+            })
         })
         .with_function("growing-tree (last)", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| growing_tree(&mut grid, last_selection::<Grid>))
+            b.iter(|| {
+                grid.reset_links();
+                growing_tree(&mut grid, last_selection::<Grid, _>, &mut rand::thread_rng())
+            })
         })
         .with_function("growing-tree (random)", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| growing_tree(&mut grid, random_selection::<Grid>))
+            b.iter(|| {
+                grid.reset_links();
+                growing_tree(&mut grid, random_selection::<Grid, _>, &mut rand::thread_rng())
+            })
         })
         .with_function("growing-tree (mixed)", |b, i| {
             let mut grid = Grid::new(*i, *i);
-            b.iter(|| growing_tree(&mut grid, mixed_selection::<Grid>))
+            b.iter(|| {
+                grid.reset_links();
+                growing_tree(&mut grid, mixed_selection::<Grid, _>, &mut rand::thread_rng())
+            })
         });
 
     c.bench("Maze algorithms for NxN grids", square_benchmark);
 }
 
-criterion_group!(benches, criterion_benchmark);
+// braided so that there is more than one route for the solvers to weigh up
+fn braided(size: usize) -> Grid {
+    let mut rng = rand::thread_rng();
+    let mut grid = Grid::new(size, size);
+    recursive_backtracker(&mut grid, &mut rng);
+    braid(&mut grid, 0.5, &mut rng);
+    grid
+}
+
+fn solver_benchmark(c: &mut Criterion) {
+    let parameters = vec![20, 40, 80];
+    let solver_benchmark = ParameterizedBenchmark::new(
+        "solve",
+        |b, i| {
+            let mut grid = braided(*i);
+            let goal = Position::new(*i - 1, *i - 1);
+            b.iter(|| solve(&mut grid, &Position::new(0, 0), &goal))
+        },
+        parameters,
+    ).with_function("astar (manhattan)", |b, i| {
+        let grid = braided(*i);
+        let goal = Position::new(*i - 1, *i - 1);
+        b.iter(|| astar(&grid, &Position::new(0, 0), &goal, manhattan))
+    });
+
+    c.bench("Solvers for braided NxN grids", solver_benchmark);
+}
+
+criterion_group!(benches, criterion_benchmark, solver_benchmark);
 criterion_main!(benches);
@@ -1,4 +1,5 @@
 extern crate meanderer;
+extern crate rand;
 
 use meanderer::algorithms::aldous_broder;
 use meanderer::data::{Grid, MazeGrid, Position};
@@ -6,10 +7,11 @@ use meanderer::rendering::{default_color_fn, png, StyleBuilder};
 use meanderer::solver::{dijkstra, solve};
 
 fn main() {
+    let mut rng = rand::thread_rng();
     let width = 30;
     let height = 30;
     let mut grid = Grid::new(width, height);
-    aldous_broder(&mut grid);
+    aldous_broder(&mut grid, &mut rng);
     solve(
         &mut grid,
         &Position::new(0, 0),
@@ -0,0 +1,45 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::{recursive_backtracker_steps, wilsons_steps};
+use meanderer::animation::{gif, GifOptions};
+use meanderer::data::{Grid, PolarGrid, Position};
+use meanderer::rendering::{default_color_fn, StyleBuilder};
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let style = StyleBuilder::new()
+        .cell_size(20)
+        .color_fn(default_color_fn)
+        .draw_solution()
+        .build();
+
+    let width = 15;
+    let height = 15;
+    let mut grid = Grid::new(width, height);
+    gif(
+        &mut grid,
+        |g, emit| recursive_backtracker_steps(g, &mut rng, emit),
+        &Position::new(0, 0),
+        &Position::new(height - 1, width - 1),
+        &style,
+        &GifOptions::default(),
+        "animated.gif",
+    ).unwrap();
+
+    // larger mazes need to skip frames to keep the file size down
+    let rows = 10;
+    let mut grid = PolarGrid::new(rows);
+    gif(
+        &mut grid,
+        |g, emit| wilsons_steps(g, &mut rng, emit),
+        &Position::new(0, 0),
+        &Position::new(rows - 1, 0),
+        &style,
+        &GifOptions {
+            frame_skip: 4,
+            ..GifOptions::default()
+        },
+        "animated_circle.gif",
+    ).unwrap();
+}
@@ -1,10 +1,12 @@
 extern crate meanderer;
+extern crate rand;
 
 use meanderer::algorithms::binary;
 use meanderer::data::{Grid, MazeGrid};
 
 fn main() {
+    let mut rng = rand::thread_rng();
     let mut grid = Grid::new(6, 6);
-    binary(&mut grid);
+    binary(&mut grid, &mut rng);
     println!("{}", grid.to_string(false));
 }
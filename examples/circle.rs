@@ -1,15 +1,17 @@
 extern crate meanderer;
+extern crate rand;
 
 use meanderer::algorithms::iterative_backtracker;
 use meanderer::data::{PolarGrid, Position};
 use meanderer::rendering::{default_color_fn, polar_png, StyleBuilder};
-use meanderer::solver::{dijkstra, furthest_on_rim, solve};
+use meanderer::solver::{furthest_on_rim, solve};
 
 fn main() {
+    let mut rng = rand::thread_rng();
     let rows = 20;
     let mut grid = PolarGrid::new(rows);
 
-    iterative_backtracker(&mut grid);
+    iterative_backtracker(&mut grid, &mut rng);
 
     let start = Position::new(0, 0);
     let end = furthest_on_rim(&mut grid, &start);
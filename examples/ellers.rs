@@ -0,0 +1,34 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::{ellers, write_ellers};
+use meanderer::data::{Grid, Position};
+use meanderer::rendering::{default_color_fn, png, StyleBuilder};
+use meanderer::solver::{dijkstra, furthest_corners, solve};
+use std::fs::File;
+use std::io::BufWriter;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 20;
+    let height = 20;
+    let mut grid = Grid::new(width, height);
+    ellers(&mut grid, &mut rng);
+
+    let (start, end) = furthest_corners(&mut grid);
+    solve(&mut grid, &start, &end);
+
+    dijkstra(&mut grid, &Position::new((height - 1) / 2, (width - 1) / 2));
+    png(
+        &grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .build(),
+        "ellers.png",
+    );
+
+    // the streaming mode never holds more than a row in memory
+    let mut out = BufWriter::new(File::create("ellers.txt").unwrap());
+    write_ellers(width, Some(10_000), &mut rng, &mut out).unwrap();
+}
@@ -1,17 +1,19 @@
 extern crate meanderer;
+extern crate rand;
 
-use meanderer::algorithms::{growing_tree, last_selection, mixed_selection, random_selection};
+use meanderer::algorithms::{growing_tree, last_selection};
 use meanderer::data::{Grid, Position};
 use meanderer::rendering::{default_color_fn, png, StyleBuilder};
 use meanderer::solver::{dijkstra, furthest_corners, solve};
 
 fn main() {
+    let mut rng = rand::thread_rng();
     let width = 50;
     let height = 50;
     let mut grid = Grid::new(width, height);
-    growing_tree(&mut grid, last_selection::<Grid>);
-    // growing_tree(&mut grid, random_selection::<Grid>);
-    // growing_tree(&mut grid, mixed_selection::<Grid>);
+    growing_tree(&mut grid, last_selection::<Grid, _>, &mut rng);
+    // growing_tree(&mut grid, random_selection::<Grid, _>, &mut rng);
+    // growing_tree(&mut grid, mixed_selection::<Grid, _>, &mut rng);
 
     let (start, end) = furthest_corners(&mut grid);
     solve(&mut grid, &start, &end);
@@ -0,0 +1,29 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::recursive_backtracker;
+use meanderer::data::{HexGrid, Position};
+use meanderer::rendering::{default_color_fn, hex_png, StyleBuilder};
+use meanderer::solver::solve;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 30;
+    let height = 25;
+    let mut grid = HexGrid::new(width, height);
+
+    recursive_backtracker(&mut grid, &mut rng);
+
+    let start = Position::new(0, 0);
+    let end = Position::new(height - 1, width - 1);
+    solve(&mut grid, &start, &end);
+
+    hex_png(
+        &grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .build(),
+        "hex.png",
+    )
+}
@@ -0,0 +1,34 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::Algorithm;
+use meanderer::data::{Grid, Position};
+use meanderer::solver::{dead_end_filling, tremaux, wall_follower, Hand};
+use rand::{SeedableRng, StdRng};
+
+// how many steps each way of solving by hand takes through the same mazes,
+// which says more about how hard a maze feels than its shortest path does
+fn main() {
+    let width = 30;
+    let height = 30;
+    let start = Position::new(0, 0);
+    let end = Position::new(height - 1, width - 1);
+
+    println!(
+        "{:<24}{:>8}{:>8}{:>8}{:>8}",
+        "algorithm", "left", "right", "tremaux", "filled"
+    );
+    for algorithm in Algorithm::all() {
+        let mut grid = Grid::new(width, height);
+        algorithm.generate(&mut grid, &mut StdRng::seed_from_u64(1));
+
+        println!(
+            "{:<24}{:>8}{:>8}{:>8}{:>8}",
+            algorithm.name(),
+            wall_follower(&grid, &start, &end, Hand::Left).steps(),
+            wall_follower(&grid, &start, &end, Hand::Right).steps(),
+            tremaux(&grid, &start, &end).steps(),
+            dead_end_filling(&grid, &start, &end).steps()
+        );
+    }
+}
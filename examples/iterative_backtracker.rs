@@ -1,4 +1,5 @@
 extern crate meanderer;
+extern crate rand;
 
 use meanderer::algorithms::{braid, iterative_backtracker};
 use meanderer::data::{Grid, Position};
@@ -6,13 +7,14 @@ use meanderer::rendering::{default_color_fn, png, StyleBuilder};
 use meanderer::solver::{dijkstra, furthest_corners, solve};
 
 fn main() {
+    let mut rng = rand::thread_rng();
     let width = 50;
     let height = 50;
     let mut grid = Grid::new(width, height);
-    iterative_backtracker(&mut grid);
+    iterative_backtracker(&mut grid, &mut rng);
 
     // remove 10 percent of the dead ends in the maze
-    braid(&mut grid, 0.1);
+    braid(&mut grid, 0.1, &mut rng);
 
     let (start, end) = furthest_corners(&mut grid);
     solve(&mut grid, &start, &end);
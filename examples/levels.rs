@@ -0,0 +1,30 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::recursive_backtracker;
+use meanderer::data::{Grid3D, Position3D};
+use meanderer::rendering::{default_color_fn, grid_3d_png, StyleBuilder};
+use meanderer::solver::solve;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let levels = 3;
+    let width = 8;
+    let height = 8;
+    let mut grid = Grid3D::new(levels, width, height);
+
+    recursive_backtracker(&mut grid, &mut rng);
+
+    let start = Position3D::new(0, 0, 0);
+    let end = Position3D::new(levels - 1, height - 1, width - 1);
+    solve(&mut grid, &start, &end);
+
+    grid_3d_png(
+        &grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .build(),
+        "levels.png",
+    )
+}
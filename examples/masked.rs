@@ -0,0 +1,40 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::recursive_backtracker;
+use meanderer::data::{Mask, MaskedGrid, MazeGrid};
+use meanderer::rendering::{default_color_fn, masked_png, StyleBuilder};
+use meanderer::solver::dijkstra;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let mask = Mask::from_txt(
+        "\
+X........X
+....XX....
+...XXXX...
+....XX....
+X........X
+X........X
+....XX....
+...XXXX...
+....XX....
+X........X
+",
+    );
+    let mut grid = MaskedGrid::new(mask);
+
+    recursive_backtracker(&mut grid, &mut rng);
+
+    if let Some(start) = grid.random_pos() {
+        dijkstra(&mut grid, &start);
+    }
+
+    println!("{}", grid.to_string(false));
+
+    masked_png(
+        &grid,
+        &StyleBuilder::new().color_fn(default_color_fn).build(),
+        "masked.png",
+    )
+}
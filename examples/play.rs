@@ -0,0 +1,11 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::Algorithm;
+use meanderer::terminal::run;
+
+fn main() {
+    // arrows or hjkl to move, s shows the solution, r makes a new maze, a
+    // switches to the next algorithm and q quits
+    run(20, 10, Algorithm::RecursiveBacktracker, &mut rand::thread_rng()).unwrap();
+}
@@ -0,0 +1,34 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::{recursive_division, DivisionOptions};
+use meanderer::data::{Grid, Position};
+use meanderer::rendering::{default_color_fn, png, StyleBuilder};
+use meanderer::solver::{dijkstra, furthest_corners, solve};
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 20;
+    let height = 20;
+    let mut grid = Grid::new(width, height);
+    // leave some open rooms around the maze
+    let options = DivisionOptions {
+        room_size: 4,
+        room_chance: 0.25,
+        ..DivisionOptions::default()
+    };
+    recursive_division(&mut grid, &options, &mut rng);
+
+    let (start, end) = furthest_corners(&mut grid);
+    solve(&mut grid, &start, &end);
+
+    dijkstra(&mut grid, &Position::new((height - 1) / 2, (width - 1) / 2));
+    png(
+        &grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .build(),
+        "recursive_division.png",
+    )
+}
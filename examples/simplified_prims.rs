@@ -1,4 +1,5 @@
 extern crate meanderer;
+extern crate rand;
 
 use meanderer::algorithms::simplified_prims;
 use meanderer::data::{Grid, Position};
@@ -6,10 +7,11 @@ use meanderer::rendering::{default_color_fn, png, StyleBuilder};
 use meanderer::solver::{dijkstra, furthest_corners, solve};
 
 fn main() {
+    let mut rng = rand::thread_rng();
     let width = 20;
     let height = 20;
     let mut grid = Grid::new(width, height);
-    simplified_prims(&mut grid);
+    simplified_prims(&mut grid, &mut rng);
 
     let (start, end) = furthest_corners(&mut grid);
     solve(&mut grid, &start, &end);
@@ -0,0 +1,24 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::{wilsons_steps, Event, Steps};
+use meanderer::data::{Grid, MazeGrid};
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let mut grid = Grid::new(8, 8);
+
+    let mut steps = Steps::new(&mut grid, |g, emit| wilsons_steps(g, &mut rng, emit));
+
+    // print a frame every time the maze changes
+    while let Some(event) = steps.next() {
+        match event {
+            Event::Link(a, b) => {
+                println!("link {:?} -> {:?}", a, b);
+                println!("{}", steps.grid().to_string(false));
+            }
+            Event::WalkErased(path) => println!("erased {} cells from the walk", path.len()),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,30 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::wilsons;
+use meanderer::data::{Grid, MazeGrid, Topology};
+use meanderer::rendering::{default_color_fn, png, StyleBuilder};
+use meanderer::solver::{furthest_corners, solve};
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 20;
+    let height = 20;
+    let mut grid = Grid::with_topology(width, height, Topology::Torus);
+
+    wilsons(&mut grid, &mut rng);
+
+    let (start, end) = furthest_corners(&mut grid);
+    solve(&mut grid, &start, &end);
+
+    println!("{}", grid.to_string(false));
+
+    png(
+        &grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .build(),
+        "torus.png",
+    )
+}
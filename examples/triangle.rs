@@ -0,0 +1,30 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::wilsons;
+use meanderer::data::{Position, TriangleGrid};
+use meanderer::rendering::{default_color_fn, triangle_png, StyleBuilder};
+use meanderer::solver::{dijkstra, solve};
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 40;
+    let height = 20;
+    let mut grid = TriangleGrid::new(width, height);
+
+    wilsons(&mut grid, &mut rng);
+
+    let start = Position::new(0, 0);
+    let end = Position::new(height - 1, width - 1);
+    solve(&mut grid, &start, &end);
+
+    dijkstra(&mut grid, &Position::new(height / 2, width / 2));
+    triangle_png(
+        &grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .build(),
+        "triangle.png",
+    )
+}
@@ -0,0 +1,34 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::{recursive_backtracker, wilsons};
+use meanderer::data::{Grid, PolarGrid, Position};
+use meanderer::rendering::{default_color_fn, StyleBuilder};
+use meanderer::solver::{dijkstra, furthest_corners, furthest_on_rim, solve};
+use meanderer::svg::{polar_svg, svg};
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let style = StyleBuilder::new()
+        .color_fn(default_color_fn)
+        .draw_solution()
+        .build();
+
+    let width = 20;
+    let height = 20;
+    let mut grid = Grid::new(width, height);
+    recursive_backtracker(&mut grid, &mut rng);
+
+    let (start, end) = furthest_corners(&mut grid);
+    solve(&mut grid, &start, &end);
+    dijkstra(&mut grid, &Position::new((height - 1) / 2, (width - 1) / 2));
+    svg(&grid, &style, "vector.svg").unwrap();
+
+    let mut grid = PolarGrid::new(12);
+    wilsons(&mut grid, &mut rng);
+
+    let start = Position::new(0, 0);
+    let end = furthest_on_rim(&mut grid, &start);
+    solve(&mut grid, &start, &end);
+    polar_svg(&grid, &style, "vector_circle.svg").unwrap();
+}
@@ -0,0 +1,30 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::recursive_backtracker;
+use meanderer::data::{Position, WeaveGrid};
+use meanderer::rendering::{default_color_fn, png, StyleBuilder};
+use meanderer::solver::solve;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 20;
+    let height = 20;
+    let mut grid = WeaveGrid::new(width, height);
+
+    recursive_backtracker(&mut grid, &mut rng);
+
+    let start = Position::new(0, 0);
+    let end = Position::new(height - 1, width - 1);
+    solve(&mut grid, &start, &end);
+
+    png(
+        &grid.grid,
+        &StyleBuilder::new()
+            .color_fn(default_color_fn)
+            .draw_solution()
+            .inset(8)
+            .build(),
+        "weave.png",
+    )
+}
@@ -0,0 +1,41 @@
+extern crate meanderer;
+extern crate rand;
+
+use meanderer::algorithms::{braid, recursive_backtracker};
+use meanderer::data::{Grid, MazeCell, MazeGrid, Position};
+use meanderer::rendering::{default_color_fn, png, StyleBuilder};
+use meanderer::solver::solve;
+use rand::Rng;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let width = 20;
+    let height = 20;
+    let mut grid = Grid::new(width, height);
+    recursive_backtracker(&mut grid, &mut rng);
+    braid(&mut grid, 0.5, &mut rng);
+
+    let start = Position::new(0, 0);
+    let end = Position::new(height - 1, width - 1);
+    let mut lava = grid.clone();
+
+    solve(&mut grid, &start, &end);
+
+    // pour lava onto a cell halfway along the shortest path, which makes the
+    // solver take one of the loops added by braid if there is one around it
+    let path = grid.cells()
+        .iter()
+        .filter(|c| c.in_solution() && c.pos != start && c.pos != end)
+        .map(|c| c.pos.clone())
+        .collect::<Vec<Position>>();
+    let pos = rng.choose(&path).unwrap();
+    lava.get_mut(pos).unwrap().update_cost(50);
+    solve(&mut lava, &start, &end);
+
+    let style = StyleBuilder::new()
+        .color_fn(default_color_fn)
+        .draw_solution()
+        .build();
+    png(&grid, &style, "weighted_before.png");
+    png(&lava, &style, "weighted.png");
+}
@@ -1,100 +1,194 @@
 use data::cell::MazeCell;
-use data::grid::{Grid, MazeGrid};
+use data::grid::{Grid, MazeGrid, WeaveGrid};
 use data::pos::{MazePosition, Position};
 use linked_hash_set::LinkedHashSet;
-use rand;
-use rand::{Rng, ThreadRng};
-use std::collections::{HashMap, HashSet};
+use rand::Rng;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::io;
+use std::io::Write;
 use std::iter::FromIterator;
+use std::str::FromStr;
+
+// what a generator did, in the order that it did it. Every generator has a
+// *_steps form that reports these through a callback as it goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<P: MazePosition> {
+    Visit(P),
+    Link(P, P),
+    Unlink(P, P),
+    // the generator has nothing left to do at this position
+    Backtrack(P),
+    // wilsons dropped a loop from its random walk
+    WalkErased(Vec<P>),
+    // hunt and kill found a new starting point while scanning the grid
+    Hunt(P),
+}
+
+// replays events onto the grid that were recorded up front by running the generator on a copy
+pub struct Steps<'a, G: MazeGrid + 'a> {
+    grid: &'a mut G,
+    events: VecDeque<Event<<G::CellType as MazeCell>::PositionType>>,
+}
+
+impl<'a, G: MazeGrid + Clone> Steps<'a, G> {
+    pub fn new<F>(grid: &'a mut G, generator: F) -> Self
+    where
+        F: FnOnce(&mut G, &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>)),
+    {
+        let mut events = VecDeque::new();
+        let mut scratch = grid.clone();
+        generator(&mut scratch, &mut |event| events.push_back(event));
+
+        Steps { grid, events }
+    }
+
+    pub fn grid(&self) -> &G {
+        self.grid
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl<'a, G: MazeGrid> Iterator for Steps<'a, G> {
+    type Item = Event<<G::CellType as MazeCell>::PositionType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.events.pop_front()?;
+        match event {
+            Event::Link(ref a, ref b) => self.grid.link(a, b),
+            Event::Unlink(ref a, ref b) => self.grid.unlink(a, b),
+            _ => {}
+        }
+
+        Some(event)
+    }
+}
+
+fn _link<G: MazeGrid>(
+    grid: &mut G,
+    a: &<G::CellType as MazeCell>::PositionType,
+    b: &<G::CellType as MazeCell>::PositionType,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    grid.link(a, b);
+    emit(Event::Link(a.clone(), b.clone()));
+}
 
-pub fn binary(grid: &mut Grid) {
-    let mut rng = rand::thread_rng();
+pub fn binary<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    binary_steps(grid, rng, &mut |_| {});
+}
 
+pub fn binary_steps<R: Rng>(grid: &mut Grid, rng: &mut R, emit: &mut dyn FnMut(Event<Position>)) {
     for i in 0..grid.cells.len() {
         let c = grid.cells[i].pos.clone();
-        let choices: Vec<Position> = vec![
+        emit(Event::Visit(c.clone()));
+
+        let choices: Vec<Position> = [
             grid.get_pos(&Position::new(c.row + 1, c.col)), // south
             grid.get_pos(&Position::new(c.row, c.col + 1)), // east
         ].iter()
+            .flatten()
             .cloned()
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap())
             .collect();
 
         if let Some(pos) = rng.choose(&choices) {
-            grid.link(&c, pos);
+            _link(grid, &c, pos, emit);
         }
     }
 }
 
-pub fn sidewinder(grid: &mut Grid) {
-    let mut links = Vec::new();
-    let mut rng = rand::thread_rng();
+pub fn sidewinder<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    sidewinder_steps(grid, rng, &mut |_| {});
+}
 
+pub fn sidewinder_steps<R: Rng>(
+    grid: &mut Grid,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<Position>),
+) {
     for row in 0..grid.height {
         let mut run = Vec::new();
 
         for col in 0..grid.width {
-            let cell = grid.get(&Position::new(row, col)).unwrap(); // this is safe
-            run.push(cell);
+            let pos = Position::new(row, col);
+            emit(Event::Visit(pos.clone()));
+            run.push(pos.clone());
 
-            let east_bound = cell.east.is_none();
-            let south_bound = cell.south.is_none();
+            // wrapped grids have neighbors across the borders, so the bounds
+            // come from the dimensions rather than the missing neighbors
+            let east_bound = col == grid.width - 1;
+            let south_bound = row == grid.height - 1;
 
             let close = east_bound || (!south_bound && rng.gen_range(0, 3) == 0);
 
             if close {
-                if let Some(choice) = rng.choose(&run) {
-                    if let Some(ref pos) = choice.south {
-                        links.push((choice.pos.clone(), pos.clone()));
+                if !south_bound {
+                    if let Some(choice) = rng.choose(&run) {
+                        let south = grid.get(choice).and_then(|c| c.south.clone());
+                        if let Some(ref south) = south {
+                            _link(grid, choice, south, emit);
+                        }
                     }
                 }
                 run.clear();
-            } else if let Some(ref pos) = cell.east {
-                links.push((cell.pos.clone(), pos.clone()));
+            } else {
+                let east = grid.get(&pos).and_then(|c| c.east.clone());
+                if let Some(ref east) = east {
+                    _link(grid, &pos, east, emit);
+                }
             }
         }
     }
-    for (p1, p2) in links.iter() {
-        grid.link(p1, p2);
-    }
 }
 
-pub fn aldous_broder<G: MazeGrid>(grid: &mut G) {
-    let mut links = Vec::new();
-    let mut linked = HashSet::new();
-    let mut rng = rand::thread_rng();
+pub fn aldous_broder<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    aldous_broder_steps(grid, rng, &mut |_| {});
+}
 
-    if let Some(ref starting_cell) = rng.choose(&grid.cells()) {
-        let mut pos = starting_cell.pos().clone();
+pub fn aldous_broder_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    let mut linked = BTreeSet::new();
+    if let Some(mut pos) = grid.random_pos_with_rng(rng) {
+        emit(Event::Visit(pos.clone()));
         linked.insert(pos.clone());
 
         let mut unvisited = grid.cells().len() - 1;
 
         while unvisited > 0 {
-            if let Some(neighbor_pos) = rng.choose(&grid.neighbors(&pos)) {
-                if !linked.contains(neighbor_pos) {
-                    links.push((pos.clone(), neighbor_pos.clone()));
+            if let Some(neighbor_pos) = rng.choose(&grid.neighbors(&pos)).cloned() {
+                emit(Event::Visit(neighbor_pos.clone()));
+                if !linked.contains(&neighbor_pos) {
+                    _link(grid, &pos, &neighbor_pos, emit);
                     linked.insert(neighbor_pos.clone());
                     unvisited -= 1;
                 }
 
-                pos = neighbor_pos.clone();
+                pos = neighbor_pos;
             }
         }
     }
+}
 
-    for (p1, p2) in links.iter() {
-        grid.link(p1, p2);
-    }
+pub fn wilsons<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    wilsons_steps(grid, rng, &mut |_| {});
 }
 
-pub fn wilsons<G: MazeGrid>(grid: &mut G) {
-    let mut unvisited: HashSet<<G::CellType as MazeCell>::PositionType> =
+pub fn wilsons_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    let mut unvisited: BTreeSet<<G::CellType as MazeCell>::PositionType> =
         grid.cells().iter().map(|c| c.pos().clone()).collect();
-    let mut rng = rand::thread_rng();
-
-    _make_initial(&mut unvisited);
+    if let Some(initial) = _make_initial(&mut unvisited, rng) {
+        emit(Event::Visit(initial));
+    }
 
     while !unvisited.is_empty() {
         if let Some(start) = rng.choose(&unvisited
@@ -103,16 +197,18 @@ pub fn wilsons<G: MazeGrid>(grid: &mut G) {
             .collect::<Vec<<G::CellType as MazeCell>::PositionType>>())
         {
             // walk from the start to a visisted cell
+            emit(Event::Visit(start.clone()));
             let mut path = Vec::new();
             path.push(start.clone());
-            _walk(grid, &mut path, &mut unvisited);
+            _walk(grid, &mut path, &mut unvisited, rng, emit);
         }
     }
 }
 
-fn _make_initial<P: MazePosition>(unvisited: &mut HashSet<P>) -> Option<P> {
-    let mut rng = rand::thread_rng();
-
+fn _make_initial<P: MazePosition, R: Rng>(
+    unvisited: &mut BTreeSet<P>,
+    rng: &mut R,
+) -> Option<P> {
     let options = unvisited.iter().cloned().collect::<Vec<P>>();
 
     if let Some(initial) = rng.choose(&options) {
@@ -123,23 +219,24 @@ fn _make_initial<P: MazePosition>(unvisited: &mut HashSet<P>) -> Option<P> {
     None
 }
 
-fn _walk<G: MazeGrid>(
+fn _walk<G: MazeGrid, R: Rng>(
     grid: &mut G,
     path: &mut Vec<<G::CellType as MazeCell>::PositionType>,
-    unvisited: &mut HashSet<<G::CellType as MazeCell>::PositionType>,
+    unvisited: &mut BTreeSet<<G::CellType as MazeCell>::PositionType>,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
 ) {
-    let mut rng = rand::thread_rng();
-
     let mut path_set = path.iter()
         .cloned()
-        .collect::<HashSet<<G::CellType as MazeCell>::PositionType>>();
+        .collect::<BTreeSet<<G::CellType as MazeCell>::PositionType>>();
 
-    while let Some(current) = path.get(path.len() - 1).cloned() {
+    while let Some(current) = path.last().cloned() {
         let mut choices = grid.neighbors(&current);
 
         // if there is a previous position in the path, we can ensure that
-        // we don't waste time by randomly selecting that element
-        if path.len() > 1 {
+        // we don't waste time by randomly selecting that element, unless it
+        // is the only way out (corners of triangle grids, for instance)
+        if path.len() > 1 && choices.len() > 1 {
             choices.retain(|c| c != &path[path.len() - 2]);
         }
 
@@ -149,7 +246,7 @@ fn _walk<G: MazeGrid>(
                 path.push(next.clone());
                 for i in 0..path.len() {
                     if i < path.len() - 1 {
-                        grid.link(&path[i], &path[i + 1]);
+                        _link(grid, &path[i], &path[i + 1], emit);
                     }
                     unvisited.remove(&path[i]);
                 }
@@ -158,18 +255,17 @@ fn _walk<G: MazeGrid>(
                 return;
             } else if path_set.contains(next) {
                 // remove loop
-                for i in 0..path.len() {
-                    if path[i] == *next {
-                        path.truncate(i + 1);
-                        break;
-                    }
+                if let Some(i) = path.iter().position(|p| p == next) {
+                    let erased = path.split_off(i + 1);
+                    emit(Event::WalkErased(erased));
                 }
 
                 // reset the path set
                 path_set = path.iter()
                     .cloned()
-                    .collect::<HashSet<<G::CellType as MazeCell>::PositionType>>();
+                    .collect::<BTreeSet<<G::CellType as MazeCell>::PositionType>>();
             } else {
+                emit(Event::Visit(next.clone()));
                 path.push(next.clone());
                 path_set.insert(next.clone());
             }
@@ -177,31 +273,35 @@ fn _walk<G: MazeGrid>(
     }
 }
 
-pub fn hunt_and_kill<G: MazeGrid>(grid: &mut G) {
+pub fn hunt_and_kill<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    hunt_and_kill_steps(grid, rng, &mut |_| {});
+}
+
+pub fn hunt_and_kill_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
     // pick a random cell
-    let mut unvisited: HashSet<<G::CellType as MazeCell>::PositionType> =
+    let mut unvisited: BTreeSet<<G::CellType as MazeCell>::PositionType> =
         grid.cells().iter().map(|c| c.pos().clone()).collect();
-    let mut rng = rand::thread_rng();
-
-    if let Some(start) = _make_initial(&mut unvisited) {
+    if let Some(start) = _make_initial(&mut unvisited, rng) {
+        emit(Event::Visit(start.clone()));
         let mut current = start;
 
         // no tail recursion in rust yet :(
-        loop {
-            if let Some(next) = _hunt_and_kill(grid, &mut unvisited, &mut rng, &current) {
-                current = next.clone()
-            } else {
-                break;
-            }
+        while let Some(next) = _hunt_and_kill(grid, &mut unvisited, rng, &current, emit) {
+            current = next
         }
     }
 }
 
-fn _hunt_and_kill<G: MazeGrid>(
+fn _hunt_and_kill<G: MazeGrid, R: Rng>(
     grid: &mut G,
-    unvisited: &mut HashSet<<G::CellType as MazeCell>::PositionType>,
-    rng: &mut ThreadRng,
+    unvisited: &mut BTreeSet<<G::CellType as MazeCell>::PositionType>,
+    rng: &mut R,
     current: &<G::CellType as MazeCell>::PositionType,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
 ) -> Option<<G::CellType as MazeCell>::PositionType> {
     let neighbors = grid.neighbors(current);
     let unvisited_neighbors: Vec<<G::CellType as MazeCell>::PositionType> = neighbors
@@ -214,7 +314,8 @@ fn _hunt_and_kill<G: MazeGrid>(
 
     if !unvisited_neighbors.is_empty() {
         if let Some(neighbor) = rng.choose(&unvisited_neighbors) {
-            grid.link(current, neighbor);
+            _link(grid, current, neighbor, emit);
+            emit(Event::Visit(neighbor.clone()));
             next = Some(neighbor.clone());
         }
     } else if !unvisited.is_empty() {
@@ -226,7 +327,8 @@ fn _hunt_and_kill<G: MazeGrid>(
                     .filter(|pos| grid.has_links(pos))
                     .collect::<Vec<&<G::CellType as MazeCell>::PositionType>>())
                 {
-                    grid.link(&cur, linked_neighbor);
+                    emit(Event::Hunt(cur.clone()));
+                    _link(grid, &cur, linked_neighbor, emit);
                     next = Some(cur.clone());
                     break;
                 }
@@ -241,22 +343,30 @@ fn _hunt_and_kill<G: MazeGrid>(
     next
 }
 
-pub fn recursive_backtracker<G: MazeGrid>(grid: &mut G) {
-    let mut unvisited: HashSet<<G::CellType as MazeCell>::PositionType> =
-        grid.cells().iter().map(|c| c.pos().clone()).collect();
-    let mut rng = rand::thread_rng();
+pub fn recursive_backtracker<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    recursive_backtracker_steps(grid, rng, &mut |_| {});
+}
 
-    if let Some(start) = _make_initial(&mut unvisited) {
-        _recurse(grid, &mut unvisited, &mut rng, &start);
+pub fn recursive_backtracker_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    let mut unvisited: BTreeSet<<G::CellType as MazeCell>::PositionType> =
+        grid.cells().iter().map(|c| c.pos().clone()).collect();
+    if let Some(start) = _make_initial(&mut unvisited, rng) {
+        _recurse(grid, &mut unvisited, rng, &start, emit);
     }
 }
 
-fn _recurse<G: MazeGrid>(
+fn _recurse<G: MazeGrid, R: Rng>(
     grid: &mut G,
-    unvisited: &mut HashSet<<G::CellType as MazeCell>::PositionType>,
-    rng: &mut rand::ThreadRng,
+    unvisited: &mut BTreeSet<<G::CellType as MazeCell>::PositionType>,
+    rng: &mut R,
     current: &<G::CellType as MazeCell>::PositionType,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
 ) {
+    emit(Event::Visit(current.clone()));
     unvisited.remove(current);
     loop {
         let neighbors = grid.neighbors(current);
@@ -268,21 +378,29 @@ fn _recurse<G: MazeGrid>(
 
         if !unvisited_neighbors.is_empty() {
             if let Some(neighbor) = rng.choose(&unvisited_neighbors) {
-                grid.link(current, neighbor);
-                _recurse(grid, unvisited, rng, &neighbor.clone());
+                _link(grid, current, neighbor, emit);
+                _recurse(grid, unvisited, rng, &neighbor.clone(), emit);
             }
         } else {
             break;
         }
     }
+    emit(Event::Backtrack(current.clone()));
 }
 
-pub fn iterative_backtracker<G: MazeGrid>(grid: &mut G) {
-    let mut unvisited: HashSet<<G::CellType as MazeCell>::PositionType> =
-        grid.cells().iter().map(|c| c.pos().clone()).collect();
-    let mut rng = rand::thread_rng();
+pub fn iterative_backtracker<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    iterative_backtracker_steps(grid, rng, &mut |_| {});
+}
 
-    if let Some(start) = _make_initial(&mut unvisited) {
+pub fn iterative_backtracker_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    let mut unvisited: BTreeSet<<G::CellType as MazeCell>::PositionType> =
+        grid.cells().iter().map(|c| c.pos().clone()).collect();
+    if let Some(start) = _make_initial(&mut unvisited, rng) {
+        emit(Event::Visit(start.clone()));
         let mut stack = Vec::new();
         stack.push(start.clone());
 
@@ -297,10 +415,13 @@ pub fn iterative_backtracker<G: MazeGrid>(grid: &mut G) {
 
             if !unvisited_neighbors.is_empty() {
                 if let Some(neighbor) = rng.choose(&unvisited_neighbors) {
-                    grid.link(&cur, neighbor);
+                    _link(grid, &cur, neighbor, emit);
+                    emit(Event::Visit(neighbor.clone()));
                     stack.push(cur);
                     stack.push(neighbor.clone());
                 }
+            } else {
+                emit(Event::Backtrack(cur));
             }
         }
     }
@@ -314,9 +435,20 @@ pub fn deadends<G: MazeGrid>(grid: &G) -> Vec<<G::CellType as MazeCell>::Positio
         .collect()
 }
 
-pub fn braid<G: MazeGrid>(grid: &mut G, dead_end_removal_probability: f32) {
-    let mut rng = rand::thread_rng();
+pub fn braid<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    dead_end_removal_probability: f32,
+    rng: &mut R,
+) {
+    braid_steps(grid, dead_end_removal_probability, rng, &mut |_| {});
+}
 
+pub fn braid_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    dead_end_removal_probability: f32,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
     for pos in deadends(grid) {
         if rng.gen::<f32>() >= dead_end_removal_probability {
             continue;
@@ -324,11 +456,12 @@ pub fn braid<G: MazeGrid>(grid: &mut G, dead_end_removal_probability: f32) {
 
         let mut choice = None;
 
+        // the grid's neighbors rather than the cell's, so weave grids can
+        // braid through tunnels and never into the middle of one
         if let Some(cell) = grid.get(&pos) {
-            let mut neighbors = cell.neighbors()
-                .iter()
+            let mut neighbors = grid.neighbors(&pos)
+                .into_iter()
                 .filter(|p| !cell.links().contains(p))
-                .cloned()
                 .collect::<Vec<<G::CellType as MazeCell>::PositionType>>();
 
             let best = neighbors
@@ -347,16 +480,23 @@ pub fn braid<G: MazeGrid>(grid: &mut G, dead_end_removal_probability: f32) {
         }
 
         if let Some(choice) = choice {
-            grid.link(&pos, &choice);
+            _link(grid, &pos, &choice, emit);
         }
     }
 }
 
-pub fn simplified_prims<G: MazeGrid>(grid: &mut G) {
-    let mut rng = rand::thread_rng();
+pub fn simplified_prims<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    simplified_prims_steps(grid, rng, &mut |_| {});
+}
 
-    if let Some(start) = grid.random_pos() {
-        let mut active = HashSet::new();
+pub fn simplified_prims_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    if let Some(start) = grid.random_pos_with_rng(rng) {
+        emit(Event::Visit(start.clone()));
+        let mut active = BTreeSet::new();
         active.insert(start);
 
         while !active.is_empty() {
@@ -365,14 +505,16 @@ pub fn simplified_prims<G: MazeGrid>(grid: &mut G) {
                 .cloned()
                 .collect::<Vec<<G::CellType as MazeCell>::PositionType>>())
             {
-                if let Some(neighbor) = rng.choose(&grid.neighbors(&pos)
+                if let Some(neighbor) = rng.choose(&grid.neighbors(pos)
                     .iter()
                     .filter(|n| !grid.has_links(n))
                     .collect::<Vec<&<G::CellType as MazeCell>::PositionType>>())
                 {
-                    grid.link(pos, neighbor);
+                    _link(grid, pos, neighbor, emit);
+                    emit(Event::Visit((*neighbor).clone()));
                     active.insert((*neighbor).clone());
                 } else {
+                    emit(Event::Backtrack(pos.clone()));
                     active.remove(pos);
                 }
             }
@@ -380,16 +522,23 @@ pub fn simplified_prims<G: MazeGrid>(grid: &mut G) {
     }
 }
 
-pub fn true_prims<G: MazeGrid>(grid: &mut G) {
-    let mut rng = rand::thread_rng();
+pub fn true_prims<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    true_prims_steps(grid, rng, &mut |_| {});
+}
 
-    if let Some(start) = grid.random_pos() {
+pub fn true_prims_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    if let Some(start) = grid.random_pos_with_rng(rng) {
+        emit(Event::Visit(start.clone()));
         let costs: HashMap<<G::CellType as MazeCell>::PositionType, u8> = HashMap::from_iter(
             grid.cells()
                 .iter()
                 .map(|c| (c.pos().clone(), rng.gen_range(0, 100))),
         );
-        let mut active = HashSet::new();
+        let mut active = BTreeSet::new();
         active.insert(start);
 
         while !active.is_empty() {
@@ -403,9 +552,11 @@ pub fn true_prims<G: MazeGrid>(grid: &mut G) {
                     .filter(|p| !grid.has_links(p))
                     .min_by(|x, y| costs[x].cmp(&costs[y]))
                 {
-                    grid.link(&pos, neighbor);
+                    _link(grid, &pos, neighbor, emit);
+                    emit(Event::Visit(neighbor.clone()));
                     active.insert((*neighbor).clone());
                 } else {
+                    emit(Event::Backtrack(pos.clone()));
                     active.remove(&pos);
                 }
             }
@@ -413,62 +564,1013 @@ pub fn true_prims<G: MazeGrid>(grid: &mut G) {
     }
 }
 
-pub fn growing_tree<G: MazeGrid, F>(grid: &mut G, selection_fn: F)
+pub fn growing_tree<G: MazeGrid, R: Rng, F>(grid: &mut G, selection_fn: F, rng: &mut R)
 where
-    F: Fn(&mut LinkedHashSet<<G::CellType as MazeCell>::PositionType>, &mut ThreadRng)
+    F: Fn(&mut LinkedHashSet<<G::CellType as MazeCell>::PositionType>, &mut R)
         -> Option<<G::CellType as MazeCell>::PositionType>,
 {
-    let mut rng = rand::thread_rng();
+    growing_tree_steps(grid, selection_fn, rng, &mut |_| {});
+}
 
-    if let Some(start) = grid.random_pos() {
+pub fn growing_tree_steps<G: MazeGrid, R: Rng, F>(
+    grid: &mut G,
+    selection_fn: F,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) where
+    F: Fn(&mut LinkedHashSet<<G::CellType as MazeCell>::PositionType>, &mut R)
+        -> Option<<G::CellType as MazeCell>::PositionType>,
+{
+    if let Some(start) = grid.random_pos_with_rng(rng) {
+        emit(Event::Visit(start.clone()));
         let mut active = LinkedHashSet::new();
         active.insert(start);
 
         while !active.is_empty() {
             // unwrap since we sort of want to fail hard if we ever don't get
             // something back
-            let pos = selection_fn(&mut active, &mut rng).unwrap();
+            let pos = selection_fn(&mut active, rng).unwrap();
 
             if let Some(neighbor) = rng.choose(&grid.neighbors(&pos)
                 .iter()
                 .filter(|n| !grid.has_links(n))
                 .collect::<Vec<&<G::CellType as MazeCell>::PositionType>>())
             {
-                grid.link(&pos, neighbor);
+                _link(grid, &pos, neighbor, emit);
+                emit(Event::Visit((*neighbor).clone()));
                 active.insert((*neighbor).clone());
             } else {
+                emit(Event::Backtrack(pos.clone()));
                 active.remove(&pos);
             }
         }
     }
 }
 
-pub fn random_selection<G: MazeGrid>(
+pub fn random_selection<G: MazeGrid, R: Rng>(
     active: &mut LinkedHashSet<<G::CellType as MazeCell>::PositionType>,
-    rng: &mut ThreadRng,
+    rng: &mut R,
 ) -> Option<<G::CellType as MazeCell>::PositionType> {
-    match rng.choose(&active
+    rng.choose(&active
         .iter()
         .collect::<Vec<&<G::CellType as MazeCell>::PositionType>>())
-    {
-        Some(pos) => Some((*pos).clone()),
-        None => None,
-    }
+        .map(|pos| (*pos).clone())
 }
 
-pub fn last_selection<G: MazeGrid>(
+pub fn last_selection<G: MazeGrid, R: Rng>(
     active: &mut LinkedHashSet<<G::CellType as MazeCell>::PositionType>,
-    _: &mut ThreadRng,
+    _: &mut R,
 ) -> Option<<G::CellType as MazeCell>::PositionType> {
     active.back().cloned()
 }
 
-pub fn mixed_selection<G: MazeGrid>(
+pub fn mixed_selection<G: MazeGrid, R: Rng>(
     active: &mut LinkedHashSet<<G::CellType as MazeCell>::PositionType>,
-    rng: &mut ThreadRng,
+    rng: &mut R,
 ) -> Option<<G::CellType as MazeCell>::PositionType> {
     match rng.gen_range(0, 2) {
-        0 => last_selection::<G>(active, rng),
-        _ => random_selection::<G>(active, rng),
+        0 => last_selection::<G, R>(active, rng),
+        _ => random_selection::<G, R>(active, rng),
+    }
+}
+
+// disjoint sets of cells for kruskals. Cells that are already linked in the
+// grid start out in the same set, and callers can merge more pairs or settle
+// crossings before handing the state to kruskals_with_state
+pub struct KruskalsState<P: MazePosition> {
+    edges: Vec<(P, P)>,
+    index: HashMap<P, usize>,
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+}
+
+impl<P: MazePosition> KruskalsState<P> {
+    pub fn new<G: MazeGrid>(grid: &G) -> Self
+    where
+        G::CellType: MazeCell<PositionType = P>,
+    {
+        let index: HashMap<P, usize> = grid.cells()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.pos().clone(), i))
+            .collect();
+
+        let mut state = KruskalsState {
+            edges: Vec::new(),
+            parent: (0..index.len()).collect(),
+            rank: vec![0; index.len()],
+            index,
+        };
+
+        for cell in grid.cells() {
+            for neighbor in grid.neighbors(cell.pos()) {
+                // only consider each pair once
+                if cell.pos() < &neighbor {
+                    state.edges.push((cell.pos().clone(), neighbor));
+                }
+            }
+
+            for link in cell.links() {
+                state.union(cell.pos(), link);
+            }
+        }
+
+        state
+    }
+
+    fn find(&mut self, pos: &P) -> Option<usize> {
+        let mut root = *self.index.get(pos)?;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // compress the path we just walked
+        let mut cur = self.index[pos];
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+
+        Some(root)
+    }
+
+    fn union(&mut self, a: &P, b: &P) -> bool {
+        match (self.find(a), self.find(b)) {
+            (Some(x), Some(y)) if x != y => {
+                if self.rank[x] < self.rank[y] {
+                    self.parent[x] = y;
+                } else {
+                    self.parent[y] = x;
+                    if self.rank[x] == self.rank[y] {
+                        self.rank[x] += 1;
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn can_merge(&mut self, a: &P, b: &P) -> bool {
+        match (self.find(a), self.find(b)) {
+            (Some(x), Some(y)) => x != y,
+            _ => false,
+        }
+    }
+
+    // links a and b in the grid if they are not already connected
+    pub fn merge<G: MazeGrid>(&mut self, grid: &mut G, a: &P, b: &P) -> bool
+    where
+        G::CellType: MazeCell<PositionType = P>,
+    {
+        if self.union(a, b) {
+            grid.link(a, b);
+            return true;
+        }
+
+        false
+    }
+
+    // drops the edges that haven't been tried yet from pos to its neighbors,
+    // for cells whose passages have already been decided
+    pub fn remove_edges_of(&mut self, pos: &P) {
+        self.edges.retain(|(a, b)| a != pos && b != pos);
+    }
+}
+
+impl KruskalsState<Position> {
+    // carves a passage over middle and a tunnel under it, east to west over
+    // the top when horizontal and north to south otherwise. The middle cell
+    // keeps no other edges, so kruskals can't break into the tunnel later.
+    // Refused when middle already has links, sits on the edge, or either
+    // passage would close a loop
+    pub fn add_crossing(
+        &mut self,
+        grid: &mut WeaveGrid,
+        middle: &Position,
+        horizontal: bool,
+    ) -> bool {
+        let cell = match grid.get(middle) {
+            Some(cell) if cell.links().is_empty() => cell.clone(),
+            _ => return false,
+        };
+
+        let sides = (&cell.west, &cell.east, &cell.north, &cell.south);
+        let (over_a, over_b, under_a, under_b) = match sides {
+            (Some(w), Some(e), Some(n), Some(s)) if horizontal => (w, e, n, s),
+            (Some(w), Some(e), Some(n), Some(s)) => (n, s, w, e),
+            _ => return false,
+        };
+
+        // joining the two sides over the top mustn't leave the ends of the
+        // tunnel in the same set either
+        let roots = [over_a, over_b, under_a, under_b]
+            .iter()
+            .map(|p| self.find(p))
+            .collect::<Option<Vec<usize>>>();
+        match roots {
+            Some(ref r) if r[0] != r[1] && r[2] != r[3] => {
+                let over = [r[0], r[1]];
+                if over.contains(&r[2]) && over.contains(&r[3]) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        self.remove_edges_of(middle);
+        self.merge(grid, over_a, middle);
+        self.merge(grid, middle, over_b);
+        self.merge(grid, under_a, under_b)
+    }
+}
+
+pub fn kruskals<G: MazeGrid, R: Rng>(grid: &mut G, rng: &mut R) {
+    let state = KruskalsState::new(grid);
+    kruskals_with_state(grid, state, rng);
+}
+
+pub fn kruskals_with_state<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    state: KruskalsState<<G::CellType as MazeCell>::PositionType>,
+    rng: &mut R,
+) {
+    kruskals_steps(grid, state, rng, &mut |_| {});
+}
+
+pub fn kruskals_steps<G: MazeGrid, R: Rng>(
+    grid: &mut G,
+    mut state: KruskalsState<<G::CellType as MazeCell>::PositionType>,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>),
+) {
+    let mut edges = state.edges.clone();
+    rng.shuffle(&mut edges);
+
+    for (a, b) in edges.iter() {
+        if state.union(a, b) {
+            _link(grid, a, b, emit);
+        }
+    }
+}
+
+// one row of an ellers maze. east[col] is a passage to col + 1 and south[col]
+// is a passage to the same column of the next row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EllersRow {
+    pub row: usize,
+    pub east: Vec<bool>,
+    pub south: Vec<bool>,
+}
+
+// yields the rows of an ellers maze one at a time, only ever holding the set
+// membership of the current row. A height of None never ends
+pub struct Ellers<'a, R: Rng + 'a> {
+    width: usize,
+    height: Option<usize>,
+    row: usize,
+    sets: Vec<usize>,
+    next_set: usize,
+    rng: &'a mut R,
+}
+
+impl<'a, R: Rng> Ellers<'a, R> {
+    pub fn new(width: usize, height: Option<usize>, rng: &'a mut R) -> Self {
+        Ellers {
+            width,
+            height,
+            row: 0,
+            sets: (0..width).collect(),
+            next_set: width,
+            rng,
+        }
+    }
+}
+
+impl<'a, R: Rng> Iterator for Ellers<'a, R> {
+    type Item = EllersRow;
+
+    fn next(&mut self) -> Option<EllersRow> {
+        if self.width == 0 || self.height.map_or(false, |h| self.row >= h) {
+            return None;
+        }
+
+        let last = self.height.map_or(false, |h| self.row == h - 1);
+        let mut east = vec![false; self.width];
+        let mut south = vec![false; self.width];
+
+        // randomly join adjacent sets, or all of them on the last row
+        for (col, joined) in east.iter_mut().enumerate().take(self.width - 1) {
+            if self.sets[col] != self.sets[col + 1] && (last || self.rng.gen_range(0, 2) == 0) {
+                *joined = true;
+                let (keep, old) = (self.sets[col], self.sets[col + 1]);
+                for set in self.sets.iter_mut().filter(|s| **s == old) {
+                    *set = keep;
+                }
+            }
+        }
+
+        if !last {
+            let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for (col, set) in self.sets.iter().enumerate() {
+                members.entry(*set).or_default().push(col);
+            }
+
+            // every set has to continue south at least once
+            for cols in members.values() {
+                if let Some(col) = self.rng.choose(cols) {
+                    south[*col] = true;
+                }
+                for col in cols {
+                    if self.rng.gen_range(0, 3) == 0 {
+                        south[*col] = true;
+                    }
+                }
+            }
+
+            for (col, set) in self.sets.iter_mut().enumerate() {
+                if !south[col] {
+                    *set = self.next_set;
+                    self.next_set += 1;
+                }
+            }
+        }
+
+        let row = self.row;
+        self.row += 1;
+
+        Some(EllersRow { row, east, south })
+    }
+}
+
+pub fn ellers<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    ellers_steps(grid, rng, &mut |_| {});
+}
+
+pub fn ellers_steps<R: Rng>(grid: &mut Grid, rng: &mut R, emit: &mut dyn FnMut(Event<Position>)) {
+    let (width, height) = (grid.width, grid.height);
+    for row in Ellers::new(width, Some(height), rng) {
+        for col in 0..width {
+            let pos = Position::new(row.row, col);
+            if row.east[col] {
+                _link(grid, &pos, &Position::new(row.row, col + 1), emit);
+            }
+            if row.south[col] {
+                _link(grid, &pos, &Position::new(row.row + 1, col), emit);
+            }
+        }
+    }
+}
+
+// streams an ellers maze in the same format as Grid::to_string without ever
+// building the grid
+pub fn write_ellers<R: Rng, W: Write>(
+    width: usize,
+    height: Option<usize>,
+    rng: &mut R,
+    out: &mut W,
+) -> io::Result<()> {
+    writeln!(out, "+{}", "---+".repeat(width))?;
+
+    for row in Ellers::new(width, height, rng) {
+        let mut top = "|".to_string();
+        let mut bot = "+".to_string();
+
+        for col in 0..width {
+            top += if row.east[col] { "    " } else { "   |" };
+            bot += if row.south[col] { "   +" } else { "---+" };
+        }
+
+        writeln!(out, "{}", top)?;
+        writeln!(out, "{}", bot)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivisionOptions {
+    // regions no bigger than room_size in either direction are left open
+    // with probability room_chance instead of being divided further
+    pub room_size: usize,
+    pub room_chance: f32,
+    // probability of dividing with a horizontal wall when either would do
+    pub horizontal_bias: f32,
+}
+
+impl Default for DivisionOptions {
+    fn default() -> Self {
+        DivisionOptions {
+            room_size: 0,
+            room_chance: 0.0,
+            horizontal_bias: 0.5,
+        }
+    }
+}
+
+pub fn recursive_division<R: Rng>(grid: &mut Grid, options: &DivisionOptions, rng: &mut R) {
+    recursive_division_steps(grid, options, rng, &mut |_| {});
+}
+
+pub fn recursive_division_steps<R: Rng>(
+    grid: &mut Grid,
+    options: &DivisionOptions,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<Position>),
+) {
+    // start with every passage open and add walls from there. Wrapped seams
+    // are left alone so that the divisions still produce a perfect maze
+    for row in 0..grid.height {
+        for col in 0..grid.width {
+            let pos = Position::new(row, col);
+            if col + 1 < grid.width {
+                _link(grid, &pos, &Position::new(row, col + 1), emit);
+            }
+            if row + 1 < grid.height {
+                _link(grid, &pos, &Position::new(row + 1, col), emit);
+            }
+        }
+    }
+
+    let (width, height) = (grid.width, grid.height);
+    _divide(grid, options, rng, emit, (0, 0), (height, width));
+}
+
+fn _divide<R: Rng>(
+    grid: &mut Grid,
+    options: &DivisionOptions,
+    rng: &mut R,
+    emit: &mut dyn FnMut(Event<Position>),
+    (row, col): (usize, usize),
+    (height, width): (usize, usize),
+) {
+    if height <= 1 || width <= 1 {
+        return;
+    }
+
+    if height <= options.room_size
+        && width <= options.room_size
+        && rng.gen::<f32>() < options.room_chance
+    {
+        return;
+    }
+
+    if rng.gen::<f32>() < options.horizontal_bias {
+        // wall below row + south_of, with a single passage through it
+        let south_of = rng.gen_range(0, height - 1);
+        let passage = rng.gen_range(0, width);
+
+        for c in 0..width {
+            if c != passage {
+                let pos = Position::new(row + south_of, col + c);
+                let other = Position::new(row + south_of + 1, col + c);
+                grid.unlink(&pos, &other);
+                emit(Event::Unlink(pos, other));
+            }
+        }
+
+        let below = row + south_of + 1;
+        _divide(grid, options, rng, emit, (row, col), (south_of + 1, width));
+        _divide(grid, options, rng, emit, (below, col), (height - south_of - 1, width));
+    } else {
+        // wall east of col + east_of, with a single passage through it
+        let east_of = rng.gen_range(0, width - 1);
+        let passage = rng.gen_range(0, height);
+
+        for r in 0..height {
+            if r != passage {
+                let pos = Position::new(row + r, col + east_of);
+                let other = Position::new(row + r, col + east_of + 1);
+                grid.unlink(&pos, &other);
+                emit(Event::Unlink(pos, other));
+            }
+        }
+
+        let right = col + east_of + 1;
+        _divide(grid, options, rng, emit, (row, col), (height, east_of + 1));
+        _divide(grid, options, rng, emit, (row, right), (height, width - east_of - 1));
+    }
+}
+
+// the generators that work on a plain Grid, for picking one by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Binary,
+    Sidewinder,
+    AldousBroder,
+    Wilsons,
+    HuntAndKill,
+    RecursiveBacktracker,
+    IterativeBacktracker,
+    SimplifiedPrims,
+    TruePrims,
+    GrowingTree,
+    Kruskals,
+    Ellers,
+    RecursiveDivision,
+}
+
+impl Algorithm {
+    pub fn all() -> &'static [Algorithm] {
+        &[
+            Algorithm::Binary,
+            Algorithm::Sidewinder,
+            Algorithm::AldousBroder,
+            Algorithm::Wilsons,
+            Algorithm::HuntAndKill,
+            Algorithm::RecursiveBacktracker,
+            Algorithm::IterativeBacktracker,
+            Algorithm::SimplifiedPrims,
+            Algorithm::TruePrims,
+            Algorithm::GrowingTree,
+            Algorithm::Kruskals,
+            Algorithm::Ellers,
+            Algorithm::RecursiveDivision,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Algorithm::Binary => "binary",
+            Algorithm::Sidewinder => "sidewinder",
+            Algorithm::AldousBroder => "aldous-broder",
+            Algorithm::Wilsons => "wilsons",
+            Algorithm::HuntAndKill => "hunt-and-kill",
+            Algorithm::RecursiveBacktracker => "recursive-backtracker",
+            Algorithm::IterativeBacktracker => "iterative-backtracker",
+            Algorithm::SimplifiedPrims => "simplified-prims",
+            Algorithm::TruePrims => "true-prims",
+            Algorithm::GrowingTree => "growing-tree",
+            Algorithm::Kruskals => "kruskals",
+            Algorithm::Ellers => "ellers",
+            Algorithm::RecursiveDivision => "recursive-division",
+        }
+    }
+
+    // the one after this in all(), wrapping around at the end
+    pub fn next(&self) -> Algorithm {
+        let all = Algorithm::all();
+        let i = all.iter().position(|a| a == self).unwrap_or(0);
+        all[(i + 1) % all.len()]
+    }
+
+    pub fn generate<R: Rng>(&self, grid: &mut Grid, rng: &mut R) {
+        self.generate_steps(grid, rng, &mut |_| {});
+    }
+
+    pub fn generate_steps<R: Rng>(
+        &self,
+        grid: &mut Grid,
+        rng: &mut R,
+        emit: &mut dyn FnMut(Event<Position>),
+    ) {
+        match *self {
+            Algorithm::Binary => binary_steps(grid, rng, emit),
+            Algorithm::Sidewinder => sidewinder_steps(grid, rng, emit),
+            Algorithm::AldousBroder => aldous_broder_steps(grid, rng, emit),
+            Algorithm::Wilsons => wilsons_steps(grid, rng, emit),
+            Algorithm::HuntAndKill => hunt_and_kill_steps(grid, rng, emit),
+            Algorithm::RecursiveBacktracker => recursive_backtracker_steps(grid, rng, emit),
+            Algorithm::IterativeBacktracker => iterative_backtracker_steps(grid, rng, emit),
+            Algorithm::SimplifiedPrims => simplified_prims_steps(grid, rng, emit),
+            Algorithm::TruePrims => true_prims_steps(grid, rng, emit),
+            Algorithm::GrowingTree => {
+                growing_tree_steps(grid, mixed_selection::<Grid, R>, rng, emit)
+            }
+            Algorithm::Kruskals => {
+                let state = KruskalsState::new(grid);
+                kruskals_steps(grid, state, rng, emit)
+            }
+            Algorithm::Ellers => ellers_steps(grid, rng, emit),
+            Algorithm::RecursiveDivision => {
+                recursive_division_steps(grid, &DivisionOptions::default(), rng, emit)
+            }
+        }
+    }
+
+    // binary tree, sidewinder, eller's and recursive division rely on the
+    // rows and columns of a square grid
+    pub fn is_generic(&self) -> bool {
+        match *self {
+            Algorithm::Binary
+            | Algorithm::Sidewinder
+            | Algorithm::Ellers
+            | Algorithm::RecursiveDivision => false,
+            _ => true,
+        }
+    }
+
+    // generates on any kind of grid, for the algorithms that can
+    pub fn generate_generic<G: MazeGrid, R: Rng>(
+        &self,
+        grid: &mut G,
+        rng: &mut R,
+    ) -> Result<(), String> {
+        match *self {
+            Algorithm::AldousBroder => aldous_broder(grid, rng),
+            Algorithm::Wilsons => wilsons(grid, rng),
+            Algorithm::HuntAndKill => hunt_and_kill(grid, rng),
+            Algorithm::RecursiveBacktracker => recursive_backtracker(grid, rng),
+            Algorithm::IterativeBacktracker => iterative_backtracker(grid, rng),
+            Algorithm::SimplifiedPrims => simplified_prims(grid, rng),
+            Algorithm::TruePrims => true_prims(grid, rng),
+            Algorithm::GrowingTree => growing_tree(grid, mixed_selection::<G, R>, rng),
+            Algorithm::Kruskals => kruskals(grid, rng),
+            _ => return Err(format!("{} only works on square grids", self)),
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase().replace('_', "-");
+        Algorithm::all()
+            .iter()
+            .find(|a| a.name() == name)
+            .cloned()
+            .ok_or_else(|| {
+                let names = Algorithm::all().iter().map(|a| a.name()).collect::<Vec<&str>>();
+                format!("unknown algorithm '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test_algorithms {
+    use super::*;
+    use analysis::validate;
+    use data::grid::{PolarGrid, WeaveGrid};
+    use rand::{SeedableRng, StdRng};
+
+    fn seeded(seed: u8) -> StdRng {
+        StdRng::from_seed([seed; 32])
+    }
+
+    fn generate<F>(seed: u8, generator: F) -> String
+    where
+        F: Fn(&mut Grid, &mut StdRng),
+    {
+        let mut grid = Grid::new(12, 12);
+        generator(&mut grid, &mut seeded(seed));
+        grid.to_string(false)
+    }
+
+    fn polar_links<F>(seed: u8, generator: F) -> Vec<Vec<Position>>
+    where
+        F: Fn(&mut PolarGrid, &mut StdRng),
+    {
+        let mut grid = PolarGrid::new(8);
+        generator(&mut grid, &mut seeded(seed));
+        grid.cells()
+            .iter()
+            .map(|c| c.links().iter().cloned().collect())
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        let generators: Vec<fn(&mut Grid, &mut StdRng)> = vec![
+            binary,
+            sidewinder,
+            aldous_broder,
+            wilsons,
+            hunt_and_kill,
+            recursive_backtracker,
+            iterative_backtracker,
+            simplified_prims,
+            true_prims,
+            kruskals,
+            ellers,
+            |g, r| recursive_division(g, &DivisionOptions::default(), r),
+            |g, r| growing_tree(g, last_selection::<Grid, _>, r),
+            |g, r| growing_tree(g, random_selection::<Grid, _>, r),
+            |g, r| growing_tree(g, mixed_selection::<Grid, _>, r),
+            |g, r| {
+                recursive_backtracker(g, r);
+                braid(g, 0.5, r);
+            },
+        ];
+
+        for generator in generators {
+            assert_eq!(generate(7, generator), generate(7, generator));
+            assert_ne!(generate(7, generator), generate(8, generator));
+        }
+    }
+
+    #[test]
+    fn same_seed_same_polar_maze() {
+        let generators: Vec<fn(&mut PolarGrid, &mut StdRng)> = vec![
+            aldous_broder,
+            wilsons,
+            hunt_and_kill,
+            recursive_backtracker,
+            iterative_backtracker,
+            simplified_prims,
+            true_prims,
+            kruskals,
+            |g, r| growing_tree(g, mixed_selection::<PolarGrid, _>, r),
+        ];
+
+        for generator in generators {
+            assert_eq!(polar_links(3, generator), polar_links(3, generator));
+            assert_ne!(polar_links(3, generator), polar_links(4, generator));
+        }
+    }
+
+    #[test]
+    fn kruskals_makes_a_perfect_maze() {
+        let mut grid = Grid::new(10, 8);
+        kruskals(&mut grid, &mut seeded(1));
+
+        let num_links: usize = grid.cells().iter().map(|c| c.links().len()).sum();
+        assert_eq!(num_links / 2, 10 * 8 - 1);
+        assert!(grid.cells().iter().all(|c| !c.links().is_empty()));
+    }
+
+    #[test]
+    fn kruskals_state_merging() {
+        let mut grid = Grid::new(3, 3);
+        let a = Position::new(0, 0);
+        let b = Position::new(0, 1);
+        let c = Position::new(1, 1);
+
+        let mut state = KruskalsState::new(&grid);
+        assert!(state.can_merge(&a, &b));
+        assert!(state.merge(&mut grid, &a, &b));
+        assert!(grid.get(&a).unwrap().is_linked_pos(&b));
+
+        // already in the same set
+        assert!(!state.can_merge(&b, &a));
+        assert!(!state.merge(&mut grid, &b, &a));
+
+        assert!(state.merge(&mut grid, &b, &c));
+        assert!(!state.can_merge(&a, &c));
+
+        kruskals_with_state(&mut grid, state, &mut seeded(2));
+
+        // the forced passages survive and the result is still a tree
+        assert!(grid.get(&a).unwrap().is_linked_pos(&b));
+        assert!(grid.get(&b).unwrap().is_linked_pos(&c));
+        let num_links: usize = grid.cells().iter().map(|c| c.links().len()).sum();
+        assert_eq!(num_links / 2, 8);
+    }
+
+    #[test]
+    fn kruskals_crossings() {
+        let mut grid = WeaveGrid::new(5, 5);
+        let middle = Position::new(2, 2);
+        let mut state = KruskalsState::new(&grid);
+
+        assert!(state.add_crossing(&mut grid, &middle, true));
+        assert!(!state.add_crossing(&mut grid, &middle, false));
+        assert!(!state.add_crossing(&mut grid, &Position::new(0, 2), true));
+        assert!(!state.add_crossing(&mut grid, &Position::new(2, 4), false));
+        assert!(state.add_crossing(&mut grid, &Position::new(1, 1), false));
+
+        kruskals_with_state(&mut grid, state, &mut seeded(3));
+
+        // the crossings are still there, with nothing else into their middles
+        let cell = grid.get(&middle).unwrap();
+        assert_eq!(
+            cell.links().iter().cloned().collect::<Vec<_>>(),
+            vec![Position::new(2, 1), Position::new(2, 3)]
+        );
+        assert!(grid.get(&Position::new(1, 2)).unwrap().is_linked_pos(&Position::new(3, 2)));
+        assert_eq!(grid.get(&Position::new(1, 1)).unwrap().links().len(), 2);
+        assert!(grid.get(&Position::new(1, 0)).unwrap().is_linked_pos(&Position::new(1, 2)));
+        assert!(validate(&grid).is_perfect());
+    }
+
+    #[test]
+    fn kruskals_state_respects_existing_links() {
+        let mut grid = Grid::new(2, 2);
+        let a = Position::new(0, 0);
+        let b = Position::new(1, 0);
+        grid.link(&a, &b);
+
+        let mut state = KruskalsState::new(&grid);
+        assert!(!state.can_merge(&a, &b));
+        assert!(state.can_merge(&a, &Position::new(1, 1)));
+    }
+
+    #[test]
+    fn ellers_makes_a_perfect_maze() {
+        let mut grid = Grid::new(9, 7);
+        ellers(&mut grid, &mut seeded(5));
+
+        let num_links: usize = grid.cells().iter().map(|c| c.links().len()).sum();
+        assert_eq!(num_links / 2, 9 * 7 - 1);
+
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![Position::new(0, 0)];
+        while let Some(pos) = stack.pop() {
+            if seen.insert(pos.clone()) {
+                stack.extend(grid.links(&pos));
+            }
+        }
+        assert_eq!(seen.len(), 9 * 7);
+    }
+
+    #[test]
+    fn ellers_streaming_matches_grid() {
+        let mut grid = Grid::new(9, 7);
+        ellers(&mut grid, &mut seeded(5));
+
+        let mut out = Vec::new();
+        write_ellers(9, Some(7), &mut seeded(5), &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), grid.to_string(false));
+    }
+
+    #[test]
+    fn ellers_unbounded() {
+        let mut rng = seeded(9);
+        let rows = Ellers::new(4, None, &mut rng).take(1000).collect::<Vec<EllersRow>>();
+
+        assert_eq!(rows.len(), 1000);
+        assert_eq!(rows[999].row, 999);
+        assert!(rows.iter().all(|r| r.south.iter().any(|s| *s)));
+    }
+
+    fn num_links<G: MazeGrid>(grid: &G) -> usize {
+        grid.cells().iter().map(|c| c.links().len()).sum::<usize>() / 2
+    }
+
+    #[test]
+    fn recursive_division_makes_a_perfect_maze() {
+        let mut grid = Grid::new(11, 6);
+        recursive_division(&mut grid, &DivisionOptions::default(), &mut seeded(4));
+
+        assert_eq!(num_links(&grid), 11 * 6 - 1);
+        assert!(grid.cells().iter().all(|c| !c.links().is_empty()));
+    }
+
+    #[test]
+    fn recursive_division_rooms() {
+        let options = DivisionOptions {
+            room_size: 20,
+            room_chance: 1.0,
+            ..DivisionOptions::default()
+        };
+
+        // the whole grid fits in a room so nothing gets divided
+        let mut grid = Grid::new(5, 5);
+        recursive_division(&mut grid, &options, &mut seeded(4));
+        assert_eq!(num_links(&grid), 2 * 5 * 4);
+    }
+
+    #[test]
+    fn recursive_division_bias() {
+        let horizontal = DivisionOptions {
+            horizontal_bias: 1.0,
+            ..DivisionOptions::default()
+        };
+        let mut grid = Grid::new(6, 6);
+        recursive_division(&mut grid, &horizontal, &mut seeded(4));
+        assert!(grid.cells().iter().all(|c| match c.east {
+            Some(ref pos) => c.is_linked_pos(pos),
+            None => true,
+        }));
+
+        let vertical = DivisionOptions {
+            horizontal_bias: 0.0,
+            ..DivisionOptions::default()
+        };
+        let mut grid = Grid::new(6, 6);
+        recursive_division(&mut grid, &vertical, &mut seeded(4));
+        assert!(grid.cells().iter().all(|c| match c.south {
+            Some(ref pos) => c.is_linked_pos(pos),
+            None => true,
+        }));
+    }
+
+    #[test]
+    fn steps_replay_the_generator() {
+        let mut expected = Grid::new(10, 10);
+        wilsons(&mut expected, &mut seeded(6));
+
+        let mut grid = Grid::new(10, 10);
+        let mut rng = seeded(6);
+        {
+            let mut steps = Steps::new(&mut grid, |g, emit| wilsons_steps(g, &mut rng, emit));
+            let total = steps.remaining();
+
+            // nothing happens to the grid until we step through
+            assert_eq!(num_links(steps.grid()), 0);
+            assert_eq!(steps.by_ref().take(total / 2).count(), total / 2);
+            assert_eq!(steps.remaining(), total - total / 2);
+            assert_eq!(steps.count(), total - total / 2);
+        }
+
+        assert_eq!(grid.to_string(false), expected.to_string(false));
+    }
+
+    #[test]
+    fn steps_events() {
+        let mut grid = Grid::new(10, 10);
+        let mut events = Vec::new();
+        wilsons_steps(&mut grid, &mut seeded(6), &mut |e| events.push(e));
+        assert!(events.iter().any(|e| match *e {
+            Event::WalkErased(ref path) => !path.is_empty(),
+            _ => false,
+        }));
+
+        let mut grid = Grid::new(10, 10);
+        let mut events = Vec::new();
+        hunt_and_kill_steps(&mut grid, &mut seeded(6), &mut |e| events.push(e));
+        let hunted = |e: &Event<Position>| match *e {
+            Event::Hunt(_) => true,
+            _ => false,
+        };
+        assert!(events.iter().any(hunted));
+
+        // every cell is eventually backtracked out of
+        let mut grid = Grid::new(10, 10);
+        let mut events = Vec::new();
+        recursive_backtracker_steps(&mut grid, &mut seeded(6), &mut |e| events.push(e));
+        let backtracks = events
+            .iter()
+            .filter(|e| match **e {
+                Event::Backtrack(_) => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(backtracks, 100);
+
+        // division removes walls from a fully linked grid
+        let mut grid = Grid::new(10, 10);
+        let mut events = Vec::new();
+        let options = DivisionOptions::default();
+        recursive_division_steps(&mut grid, &options, &mut seeded(6), &mut |e| events.push(e));
+        let links = events
+            .iter()
+            .filter(|e| match **e {
+                Event::Link(_, _) => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(links, 2 * 10 * 9);
+        assert_eq!(links - (events.len() - links), 99);
+    }
+
+    #[test]
+    fn braiding_keeps_tunnels() {
+        for seed in 0..27 {
+            let mut grid = WeaveGrid::new(12, 12);
+            recursive_backtracker(&mut grid, &mut seeded(seed));
+            braid(&mut grid, 1.0, &mut seeded(seed));
+
+            let validation = validate(&grid);
+            assert!(validation.is_valid(), "seed {}: {:?}", seed, validation.problems);
+            assert!(deadends(&grid).is_empty());
+        }
+    }
+
+    #[test]
+    fn algorithm_names() {
+        for algorithm in Algorithm::all() {
+            assert_eq!(algorithm.name().parse::<Algorithm>(), Ok(*algorithm));
+            assert_eq!(algorithm.to_string(), algorithm.name());
+        }
+
+        assert_eq!("Hunt_And_Kill".parse::<Algorithm>(), Ok(Algorithm::HuntAndKill));
+        assert!("nope".parse::<Algorithm>().unwrap_err().contains("wilsons"));
+
+        assert_eq!(Algorithm::Binary.next(), Algorithm::Sidewinder);
+        assert_eq!(Algorithm::RecursiveDivision.next(), Algorithm::Binary);
+    }
+
+    #[test]
+    fn algorithms_make_perfect_mazes() {
+        for algorithm in Algorithm::all() {
+            let mut grid = Grid::new(7, 5);
+            algorithm.generate(&mut grid, &mut seeded(3));
+            assert_eq!(num_links(&grid), 7 * 5 - 1, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn generic_algorithms() {
+        for algorithm in Algorithm::all() {
+            let mut grid = PolarGrid::new(4);
+            let result = algorithm.generate_generic(&mut grid, &mut seeded(3));
+            if algorithm.is_generic() {
+                assert_eq!(num_links(&grid), grid.cells().len() - 1, "{}", algorithm);
+            } else {
+                assert!(result.is_err());
+                assert_eq!(num_links(&grid), 0);
+            }
+        }
     }
 }
@@ -0,0 +1,506 @@
+use data::cell::{Cell, MazeCell};
+use data::grid::{Grid, MazeGrid};
+use data::pos::{MazePosition, Position};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+// the texture of a maze, for comparing what different generators produce.
+// Lengths are counted in steps between cells and ignore traversal costs
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Report {
+    pub cells: usize,
+    pub links: usize,
+    pub dead_ends: usize,
+    pub dead_end_ratio: f32,
+    // how many cells have each number of links, for three or more
+    pub junctions: BTreeMap<usize, usize>,
+    // the most steps it takes to get between two connected cells
+    pub diameter: usize,
+    // runs of cells with exactly two links
+    pub corridors: usize,
+    pub average_corridor_length: f32,
+    // the share of cells that are part of a corridor. Mazes with long winding
+    // passages and few branches score close to 1
+    pub river_factor: f32,
+    // cells in corridors that carry on in the direction they were entered,
+    // and those that turn
+    pub straight: usize,
+    pub turning: usize,
+    pub straight_ratio: f32,
+    pub components: usize,
+    pub loops: usize,
+    pub link_bias: Option<LinkBias>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct LinkBias {
+    pub horizontal: usize,
+    pub vertical: usize,
+    // horizontal links over all links, so 0.5 is unbiased
+    pub horizontal_ratio: f32,
+}
+
+fn _ratio(part: usize, whole: usize) -> f32 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f32 / whole as f32
+    }
+}
+
+// steps from start to every cell it is connected to
+fn _steps_from<G: MazeGrid>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+) -> HashMap<<G::CellType as MazeCell>::PositionType, usize> {
+    let mut steps = HashMap::new();
+    let mut queue = VecDeque::new();
+    steps.insert(start.clone(), 0);
+    queue.push_back(start.clone());
+
+    while let Some(pos) = queue.pop_front() {
+        let dist = steps[&pos];
+        if let Some(cell) = grid.get(&pos) {
+            for link in cell.links() {
+                if !steps.contains_key(link) {
+                    steps.insert(link.clone(), dist + 1);
+                    queue.push_back(link.clone());
+                }
+            }
+        }
+    }
+
+    steps
+}
+
+// the cells of every group that is connected through links
+fn _components<G: MazeGrid>(grid: &G) -> Vec<Vec<<G::CellType as MazeCell>::PositionType>> {
+    let mut seen = HashSet::new();
+    let mut components = Vec::new();
+
+    for pos in grid.positions() {
+        if seen.contains(&pos) {
+            continue;
+        }
+        let mut component = _steps_from(grid, &pos).keys().cloned().collect::<Vec<_>>();
+        component.sort();
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+// the furthest two cells of a tree are found by walking as far as possible
+// from anywhere, then as far as possible from there. That fails once there
+// are loops, so those components check every cell instead
+fn _diameter<G: MazeGrid>(
+    grid: &G,
+    component: &[<G::CellType as MazeCell>::PositionType],
+) -> usize {
+    let links = component.iter().map(|p| grid.num_links(p)).sum::<usize>() / 2;
+    let furthest = |pos| {
+        _steps_from(grid, pos)
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .unwrap()
+    };
+
+    if links + 1 == component.len() {
+        let (end, _) = furthest(&component[0]);
+        furthest(&end).1
+    } else {
+        component.iter().map(|p| furthest(p).1).max().unwrap_or(0)
+    }
+}
+
+// a corridor cell turns when the two cells it joins touch, or are both next
+// to some other cell, which holds for square, hex and triangle grids alike
+fn _turns<G: MazeGrid>(grid: &G, cell: &G::CellType) -> bool {
+    let ends = cell.links().iter().collect::<Vec<_>>();
+    let a = grid.neighbors(ends[0]).into_iter().collect::<BTreeSet<_>>();
+    let b = grid.neighbors(ends[1]).into_iter().collect::<BTreeSet<_>>();
+
+    a.contains(ends[1]) || a.intersection(&b).any(|p| p != cell.pos())
+}
+
+pub fn analyze<G: MazeGrid>(grid: &G) -> Report {
+    let cells = grid.cells().len();
+    let links = grid.cells().iter().map(|c| c.links().len()).sum::<usize>() / 2;
+
+    let mut dead_ends = 0;
+    let mut junctions = BTreeMap::new();
+    let mut straight = 0;
+    let mut turning = 0;
+    for cell in grid.cells() {
+        match cell.links().len() {
+            1 => dead_ends += 1,
+            2 if _turns(grid, cell) => turning += 1,
+            2 => straight += 1,
+            0 => {}
+            n => *junctions.entry(n).or_default() += 1,
+        }
+    }
+
+    // corridors are the groups of linked cells that all have two links
+    let mut seen = HashSet::new();
+    let mut corridors = 0;
+    for cell in grid.cells().iter().filter(|c| c.links().len() == 2) {
+        if !seen.insert(cell.pos().clone()) {
+            continue;
+        }
+        corridors += 1;
+
+        let mut stack = vec![cell.pos().clone()];
+        while let Some(pos) = stack.pop() {
+            for link in grid.links(&pos) {
+                if grid.num_links(&link) == 2 && seen.insert(link.clone()) {
+                    stack.push(link);
+                }
+            }
+        }
+    }
+
+    let components = _components(grid);
+    let diameter = components.iter().map(|c| _diameter(grid, c)).max().unwrap_or(0);
+
+    Report {
+        cells,
+        links,
+        dead_ends,
+        dead_end_ratio: _ratio(dead_ends, cells),
+        junctions,
+        diameter,
+        corridors,
+        average_corridor_length: _ratio(straight + turning, corridors),
+        river_factor: _ratio(straight + turning, cells),
+        straight,
+        turning,
+        straight_ratio: _ratio(straight, straight + turning),
+        components: components.len(),
+        loops: links + components.len() - cells,
+        link_bias: None,
+    }
+}
+
+// the same as analyze, plus how the links lean between the two axes
+pub fn analyze_grid(grid: &Grid) -> Report {
+    let mut report = analyze(grid);
+    report.link_bias = Some(link_bias(grid));
+    report
+}
+
+pub fn link_bias(grid: &Grid) -> LinkBias {
+    let count = |side: fn(&Cell) -> &Option<Position>| {
+        grid.cells
+            .iter()
+            .filter(|c| side(c).as_ref().map_or(false, |p| c.is_linked_pos(p)))
+            .count()
+    };
+
+    // wrapped seams can leave east and south pointing back at the same cell
+    // as west and north, so only one side of each axis is counted
+    let horizontal = count(|c| &c.east);
+    let vertical = count(|c| &c.south);
+
+    LinkBias {
+        horizontal,
+        vertical,
+        horizontal_ratio: _ratio(horizontal, horizontal + vertical),
+    }
+}
+
+// something wrong with a single link, which generators and loaders should
+// never leave behind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem<P: MazePosition> {
+    // the link points at a position with no cell
+    OutsideGrid { from: P, to: P },
+    // the cell on the other end doesn't link back
+    OneWay { from: P, to: P },
+    NotNeighbors { from: P, to: P },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validation<P: MazePosition> {
+    pub problems: Vec<Problem<P>>,
+    pub components: usize,
+    // cells without a single link
+    pub isolated: Vec<P>,
+    pub loops: usize,
+}
+
+impl<P: MazePosition> Validation<P> {
+    pub fn is_consistent(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.components <= 1
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.is_consistent() && self.is_connected()
+    }
+
+    // every cell reachable from every other in exactly one way
+    pub fn is_perfect(&self) -> bool {
+        self.is_valid() && self.loops == 0
+    }
+}
+
+pub fn validate<G: MazeGrid>(grid: &G) -> Validation<<G::CellType as MazeCell>::PositionType> {
+    let mut problems = Vec::new();
+    let mut adjacent = BTreeMap::new();
+    for cell in grid.cells() {
+        adjacent.entry(cell.pos().clone()).or_insert_with(BTreeSet::new);
+    }
+
+    for cell in grid.cells() {
+        let from = cell.pos();
+        for to in cell.links() {
+            let other = match grid.get(to) {
+                Some(other) => other,
+                None => {
+                    problems.push(Problem::OutsideGrid {
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                    continue;
+                }
+            };
+
+            let one_way = !other.is_linked_pos(from);
+            if one_way {
+                problems.push(Problem::OneWay {
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+            // links that go both ways are only reported from their first end.
+            // The grid decides what its neighbors are, so weave tunnels count
+            if !grid.neighbors(from).contains(to) && (one_way || from < to) {
+                problems.push(Problem::NotNeighbors {
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+
+            // broken links still join cells, so they count towards loops
+            // and connectivity like any other
+            adjacent.get_mut(from).unwrap().insert(to.clone());
+            adjacent.get_mut(to).unwrap().insert(from.clone());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut components = 0;
+    for pos in adjacent.keys() {
+        if !seen.insert(pos.clone()) {
+            continue;
+        }
+        components += 1;
+
+        let mut stack = vec![pos];
+        while let Some(pos) = stack.pop() {
+            for next in &adjacent[pos] {
+                if seen.insert(next.clone()) {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+
+    let links = adjacent.values().map(|a| a.len()).sum::<usize>() / 2;
+    let isolated = grid.cells()
+        .iter()
+        .filter(|c| c.links().is_empty())
+        .map(|c| c.pos().clone())
+        .collect();
+
+    Validation {
+        problems,
+        components,
+        isolated,
+        loops: links + components - adjacent.len(),
+    }
+}
+
+#[cfg(test)]
+mod test_analysis {
+    use super::*;
+    use algorithms::{binary, braid, recursive_backtracker, simplified_prims, Algorithm};
+    use data::grid::{PolarGrid, WeaveGrid};
+    use rand::{SeedableRng, StdRng};
+
+    fn seeded(seed: u8) -> StdRng {
+        StdRng::from_seed([seed; 32])
+    }
+
+    #[test]
+    fn corridor() {
+        // an L shaped corridor with a spur off the corner
+        //   0 - 1 - 2
+        //       |
+        //       3
+        let mut grid = Grid::new(3, 2);
+        grid.link(&Position::new(0, 0), &Position::new(0, 1));
+        grid.link(&Position::new(0, 1), &Position::new(0, 2));
+        grid.link(&Position::new(0, 1), &Position::new(1, 1));
+        grid.link(&Position::new(1, 0), &Position::new(1, 1));
+
+        let report = analyze_grid(&grid);
+        assert_eq!((report.cells, report.links), (6, 4));
+        assert_eq!(report.dead_ends, 3);
+        assert_eq!(report.junctions.get(&3), Some(&1));
+        assert_eq!((report.straight, report.turning), (0, 1));
+        assert_eq!((report.corridors, report.diameter), (1, 3));
+
+        // the last cell is on its own
+        assert_eq!((report.components, report.loops), (2, 0));
+
+        let bias = report.link_bias.unwrap();
+        assert_eq!((bias.horizontal, bias.vertical), (3, 1));
+        assert_eq!(bias.horizontal_ratio, 0.75);
+    }
+
+    #[test]
+    fn loops_and_diameter() {
+        let mut rng = seeded(1);
+        let mut grid = Grid::new(10, 10);
+        recursive_backtracker(&mut grid, &mut rng);
+        let perfect = analyze(&grid);
+        assert_eq!((perfect.components, perfect.loops), (1, 0));
+
+        braid(&mut grid, 1.0, &mut rng);
+        let braided = analyze(&grid);
+        assert_eq!(braided.dead_ends, 0);
+        assert_eq!(braided.loops, braided.links - perfect.links);
+
+        // the slow way round every pair of cells
+        let diameter = grid.positions()
+            .iter()
+            .map(|p| *_steps_from(&grid, p).values().max().unwrap())
+            .max()
+            .unwrap();
+        assert_eq!(braided.diameter, diameter);
+    }
+
+    #[test]
+    fn textures() {
+        let mut backtracker = Grid::new(20, 20);
+        recursive_backtracker(&mut backtracker, &mut seeded(2));
+        let mut prims = Grid::new(20, 20);
+        simplified_prims(&mut prims, &mut seeded(2));
+
+        let backtracker = analyze_grid(&backtracker);
+        let prims = analyze_grid(&prims);
+        assert!(backtracker.river_factor > prims.river_factor);
+        assert!(backtracker.dead_end_ratio < prims.dead_end_ratio);
+        assert!(backtracker.average_corridor_length > prims.average_corridor_length);
+        assert!(backtracker.diameter > prims.diameter);
+
+        // binary trees carve the whole top row and right column
+        let mut grid = Grid::new(20, 20);
+        binary(&mut grid, &mut seeded(2));
+        let report = analyze_grid(&grid);
+        assert_eq!(report.loops, 0);
+        assert!(report.diameter >= 38);
+    }
+
+    #[test]
+    fn polar() {
+        let mut grid = PolarGrid::new(6);
+        recursive_backtracker(&mut grid, &mut seeded(3));
+        let report = analyze(&grid);
+
+        assert_eq!(report.cells, grid.cells.len());
+        assert_eq!(report.links, report.cells - 1);
+        assert_eq!(report.link_bias, None);
+        let corridor_cells = report.straight + report.turning;
+        let junction_cells = report.junctions.values().sum::<usize>();
+        assert_eq!(corridor_cells + junction_cells + report.dead_ends, report.cells);
+    }
+
+    #[cfg(feature = "serialization")]
+    #[test]
+    fn serializes() {
+        use data::schema::{from_json, to_json};
+
+        let mut grid = Grid::new(5, 5);
+        recursive_backtracker(&mut grid, &mut seeded(4));
+        let report = analyze_grid(&grid);
+
+        let json = to_json(&report);
+        assert!(json.contains("\"dead_ends\":"));
+        assert!(json.contains("\"link_bias\":{\"horizontal\":"));
+        assert_eq!(from_json::<Report>(&json), Ok(report));
+    }
+
+    #[test]
+    fn generators_make_perfect_mazes() {
+        for algorithm in Algorithm::all() {
+            let mut grid = Grid::new(12, 9);
+            algorithm.generate(&mut grid, &mut seeded(5));
+            let validation = validate(&grid);
+            assert!(validation.is_perfect(), "{}: {:?}", algorithm, validation);
+            assert!(validation.isolated.is_empty());
+
+            braid(&mut grid, 0.5, &mut seeded(5));
+            let validation = validate(&grid);
+            assert!(validation.is_valid(), "{}: {:?}", algorithm, validation);
+            assert_eq!(validation.loops, analyze(&grid).loops);
+
+            if algorithm.is_generic() {
+                let mut grid = PolarGrid::new(6);
+                algorithm.generate_generic(&mut grid, &mut seeded(5)).unwrap();
+                assert!(validate(&grid).is_perfect(), "{}", algorithm);
+
+                let mut grid = WeaveGrid::new(12, 12);
+                algorithm.generate_generic(&mut grid, &mut seeded(5)).unwrap();
+                assert!(validate(&grid).is_perfect(), "{}", algorithm);
+            }
+        }
+    }
+
+    #[test]
+    fn broken_links() {
+        let mut grid = Grid::new(3, 3);
+        let validation = validate(&grid);
+        assert_eq!((validation.components, validation.isolated.len()), (9, 9));
+        assert!(validation.is_consistent() && !validation.is_connected());
+
+        let (a, b, c) = (Position::new(0, 0), Position::new(0, 1), Position::new(2, 2));
+        grid.get_mut(&a).unwrap().link(&b);
+        grid.link(&b, &c);
+        grid.get_mut(&c).unwrap().link(&Position::new(7, 7));
+
+        let validation = validate(&grid);
+        assert_eq!(
+            validation.problems,
+            vec![
+                Problem::OneWay { from: a.clone(), to: b.clone() },
+                Problem::NotNeighbors { from: b.clone(), to: c.clone() },
+                Problem::OutsideGrid { from: c.clone(), to: Position::new(7, 7) },
+            ]
+        );
+        assert_eq!(validation.components, 7);
+        assert!(!validation.isolated.contains(&a));
+        assert!(!validation.is_perfect());
+    }
+
+    #[test]
+    fn loops() {
+        let mut grid = Grid::new(2, 2);
+        grid.link(&Position::new(0, 0), &Position::new(0, 1));
+        grid.link(&Position::new(0, 1), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(1, 0));
+        assert!(validate(&grid).is_perfect());
+
+        grid.link(&Position::new(1, 0), &Position::new(0, 0));
+        let validation = validate(&grid);
+        assert!(validation.is_valid() && !validation.is_perfect());
+        assert_eq!(validation.loops, 1);
+    }
+}
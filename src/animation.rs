@@ -0,0 +1,301 @@
+use algorithms::{Event, Steps};
+use data::cell::MazeCell;
+use data::grid::{Grid, MazeGrid, PolarGrid};
+use image::gif::{Encoder, Frame};
+use image::{ImageError, ImageResult, RgbImage};
+use rendering::{grid_image, polar_image, Style};
+use solver::{dijkstra, solve};
+use std::collections::HashMap;
+use std::fs::File;
+
+// grids that can be drawn into a single animation frame
+pub trait Render: MazeGrid + Clone {
+    fn render(&self, style: &Style) -> RgbImage;
+}
+
+impl Render for Grid {
+    fn render(&self, style: &Style) -> RgbImage {
+        grid_image(self, style)
+    }
+}
+
+impl Render for PolarGrid {
+    fn render(&self, style: &Style) -> RgbImage {
+        polar_image(self, style)
+    }
+}
+
+// delays are in hundredths of a second, which is what gif uses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GifOptions {
+    // only every nth change to the maze gets its own frame. The last frame
+    // of each phase is always kept
+    pub frame_skip: usize,
+    pub delay: u16,
+    // how long to hold the last frame of each phase
+    pub pause: u16,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        GifOptions {
+            frame_skip: 1,
+            delay: 4,
+            pause: 100,
+        }
+    }
+}
+
+struct Recorder {
+    encoder: Encoder<File>,
+    options: GifOptions,
+    changes: usize,
+}
+
+impl Recorder {
+    fn frame(&mut self, img: RgbImage, delay: u16) -> ImageResult<()> {
+        let mut frame = _quantize(&img)?;
+        frame.delay = delay;
+        self.encoder.encode(&frame)
+    }
+
+    // called for every change, but only records every frame_skip changes
+    fn change<G: Render>(&mut self, grid: &G, style: &Style) -> ImageResult<()> {
+        self.changes += 1;
+        if self.changes % self.options.frame_skip.max(1) == 0 {
+            let delay = self.options.delay;
+            return self.frame(grid.render(style), delay);
+        }
+
+        Ok(())
+    }
+
+    fn pause<G: Render>(&mut self, grid: &G, style: &Style) -> ImageResult<()> {
+        self.changes = 0;
+        let delay = self.options.pause;
+        self.frame(grid.render(style), delay)
+    }
+}
+
+// most frames only use a handful of colors, and an exact palette is far
+// cheaper to build than running the gif quantizer over every frame. Gif sizes
+// are 16 bit, so anything larger is an error
+fn _quantize(img: &RgbImage) -> ImageResult<Frame<'static>> {
+    let (width, height) = img.dimensions();
+    if width > u32::from(u16::max_value()) || height > u32::from(u16::max_value()) {
+        return Err(ImageError::DimensionError);
+    }
+    let mut palette = Vec::new();
+    let mut indices: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+
+    for pixel in img.pixels() {
+        let color = [pixel[0], pixel[1], pixel[2]];
+        let index = match indices.get(&color) {
+            Some(index) => *index,
+            None if indices.len() < 256 => {
+                let index = indices.len() as u8;
+                indices.insert(color, index);
+                palette.extend_from_slice(&color);
+                index
+            }
+            None => {
+                let raw = img.clone().into_raw();
+                return Ok(Frame::from_rgb_speed(width as u16, height as u16, &raw, 10));
+            }
+        };
+        pixels.push(index);
+    }
+
+    Ok(Frame::from_palette_pixels(
+        width as u16,
+        height as u16,
+        &pixels,
+        &palette,
+        None,
+    ))
+}
+
+// records the maze being carved by the generator, then the distances flooding
+// out from start and finally the solution path growing from start to end. The
+// grid is left generated and solved
+pub fn gif<G, F>(
+    grid: &mut G,
+    generator: F,
+    start: &<G::CellType as MazeCell>::PositionType,
+    end: &<G::CellType as MazeCell>::PositionType,
+    style: &Style,
+    options: &GifOptions,
+    name: &str,
+) -> ImageResult<()>
+where
+    G: Render,
+    F: FnOnce(&mut G, &mut dyn FnMut(Event<<G::CellType as MazeCell>::PositionType>)),
+{
+    let mut recorder = Recorder {
+        encoder: Encoder::new(File::create(name)?),
+        options: options.clone(),
+        changes: 0,
+    };
+
+    let mut plain = style.clone();
+    plain.color_fn = None;
+    plain.draw_solution = false;
+
+    {
+        let mut steps = Steps::new(grid, generator);
+        while let Some(event) = steps.next() {
+            match event {
+                Event::Link(_, _) | Event::Unlink(_, _) => {
+                    recorder.change(steps.grid(), &plain)?;
+                }
+                _ => {}
+            }
+        }
+        recorder.pause(steps.grid(), &plain)?;
+    }
+
+    // cells beyond the edge of the flood are drawn as the furthest ones, so
+    // that every frame keeps the color scale of the finished flood
+    let mut flooded = grid.clone();
+    dijkstra(&mut flooded, start);
+    let max_weight = flooded.cells().iter().map(|c| c.weight()).max().unwrap_or(0);
+
+    let mut unsolved = style.clone();
+    unsolved.draw_solution = false;
+
+    for dist in 1..max_weight + 1 {
+        let mut frame = flooded.clone();
+        let beyond = frame
+            .cells()
+            .iter()
+            .filter(|c| c.weight() > dist)
+            .map(|c| c.pos().clone())
+            .collect::<Vec<<G::CellType as MazeCell>::PositionType>>();
+        for pos in beyond {
+            if let Some(cell) = frame.get_mut(&pos) {
+                cell.update_weight(max_weight);
+            }
+        }
+        recorder.change(&frame, &unsolved)?;
+    }
+    recorder.pause(&flooded, &unsolved)?;
+
+    solve(grid, start, end);
+
+    let mut path = grid.cells()
+        .iter()
+        .filter(|c| c.in_solution())
+        .map(|c| (c.weight(), c.pos().clone()))
+        .collect::<Vec<(u32, <G::CellType as MazeCell>::PositionType)>>();
+    path.sort();
+
+    let mut frame = flooded;
+    for (_, pos) in path {
+        if let Some(cell) = frame.get_mut(&pos) {
+            cell.mark_in_solution();
+        }
+        recorder.change(&frame, style)?;
+    }
+    recorder.pause(&frame, style)
+}
+
+#[cfg(test)]
+mod test_animation {
+    use super::*;
+    use algorithms::recursive_backtracker_steps;
+    use data::pos::Position;
+    use image::gif::Decoder;
+    use image::AnimationDecoder;
+    use rand::{SeedableRng, StdRng};
+    use rendering::StyleBuilder;
+    use std::path::PathBuf;
+    use std::{env, fs, process};
+
+    // named after the process so that parallel runs don't write over each other
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("meanderer_{}_{}.gif", name, process::id()))
+    }
+
+    fn count_frames(name: &str) -> usize {
+        let decoder = Decoder::new(File::open(name).unwrap()).unwrap();
+        decoder.into_frames().collect_frames().unwrap().len()
+    }
+
+    #[test]
+    fn grid_gif() {
+        let path = temp_path("grid");
+        let name = path.to_str().unwrap();
+
+        let mut grid = Grid::new(4, 4);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let start = Position::new(0, 0);
+        let end = Position::new(3, 3);
+
+        gif(
+            &mut grid,
+            |g, emit| recursive_backtracker_steps(g, &mut rng, emit),
+            &start,
+            &end,
+            &StyleBuilder::new().cell_size(4).wall_thickness(1).build(),
+            &GifOptions::default(),
+            name,
+        ).unwrap();
+
+        // 15 links, then one frame per distance and one per solution cell,
+        // each followed by a pause
+        let max_weight = grid.cells().iter().map(|c| c.weight()).max().unwrap();
+        let solution = grid.cells().iter().filter(|c| c.in_solution()).count();
+        let expected = 15 + 1 + max_weight as usize + 1 + solution + 1;
+        assert_eq!(count_frames(name), expected);
+        assert!(grid.get(&end).unwrap().in_solution());
+        fs::remove_file(name).unwrap();
+    }
+
+    #[test]
+    fn polar_gif_skipping_frames() {
+        let path = temp_path("polar");
+        let name = path.to_str().unwrap();
+
+        let mut grid = PolarGrid::new(3);
+        let mut rng = StdRng::from_seed([1; 32]);
+        let options = GifOptions {
+            frame_skip: 1000,
+            ..GifOptions::default()
+        };
+
+        gif(
+            &mut grid,
+            |g, emit| recursive_backtracker_steps(g, &mut rng, emit),
+            &Position::new(0, 0),
+            &Position::new(2, 0),
+            &StyleBuilder::new().cell_size(4).build(),
+            &options,
+            name,
+        ).unwrap();
+
+        // only the pauses at the end of each phase are left
+        assert_eq!(count_frames(name), 3);
+        fs::remove_file(name).unwrap();
+    }
+
+    #[test]
+    fn too_wide() {
+        let path = temp_path("wide");
+        let name = path.to_str().unwrap();
+
+        // 7000 cells of 10 pixels are wider than a gif can be
+        let mut grid = Grid::new(7000, 1);
+        let result = gif(
+            &mut grid,
+            |_, _| {},
+            &Position::new(0, 0),
+            &Position::new(0, 6999),
+            &StyleBuilder::new().cell_size(9).wall_thickness(1).build(),
+            &GifOptions::default(),
+            name,
+        );
+        assert!(result.is_err());
+        fs::remove_file(name).unwrap();
+    }
+}
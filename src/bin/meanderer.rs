@@ -0,0 +1,413 @@
+extern crate clap;
+extern crate image;
+extern crate meanderer;
+extern crate rand;
+
+use clap::{App, Arg, ArgMatches};
+use image::RgbImage;
+use meanderer::algorithms::{braid, Algorithm};
+use meanderer::data::{Grid, MazeCell, MazeGrid, PolarGrid, Position};
+use meanderer::rendering::{default_color_fn, grid_image, polar_image, StyleBuilder};
+use meanderer::solver::{dijkstra, furthest_corners, furthest_on_rim, longest_path, solve};
+use rand::{Rng, SeedableRng, StdRng};
+use std::fs::File;
+use std::io::Write;
+use std::process;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Square,
+    Polar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Png,
+    Ascii,
+}
+
+struct Options {
+    shape: Shape,
+    width: usize,
+    height: usize,
+    algorithm: Algorithm,
+    seed: u64,
+    braid: f32,
+    format: Format,
+    output: Option<String>,
+    solution: bool,
+    distances: bool,
+    hardest: bool,
+    style: StyleBuilder,
+}
+
+fn app<'a, 'b>() -> App<'a, 'b> {
+    App::new("meanderer")
+        .about("Generates, solves and renders mazes")
+        .arg(
+            Arg::with_name("grid")
+                .short("g")
+                .long("grid")
+                .takes_value(true)
+                .possible_values(&["square", "polar"])
+                .default_value("square")
+                .help("The shape of the grid"),
+        )
+        .arg(
+            Arg::with_name("size")
+                .short("s")
+                .long("size")
+                .takes_value(true)
+                .default_value("20")
+                .help("WIDTHxHEIGHT or a side for square grids, or the rows of polar ones"),
+        )
+        .arg(
+            Arg::with_name("algorithm")
+                .short("a")
+                .long("algorithm")
+                .takes_value(true)
+                .default_value("recursive-backtracker")
+                .help("One of the algorithms listed by --list"),
+        )
+        .arg(
+            Arg::with_name("list")
+                .long("list")
+                .help("Lists the algorithms and the grids they work on"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("Seed for the random number generator, picked at random by default"),
+        )
+        .arg(
+            Arg::with_name("braid")
+                .short("b")
+                .long("braid")
+                .takes_value(true)
+                .default_value("0")
+                .help("Probability of removing each dead end, between 0 and 1"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["png", "ascii"])
+                .default_value("png"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help("Output file, defaults to maze.png or stdout for ascii"),
+        )
+        .arg(
+            Arg::with_name("solution")
+                .long("solution")
+                .help("Draws the path between the two cells furthest apart"),
+        )
+        .arg(
+            Arg::with_name("hardest")
+                .long("hardest")
+                .help("Starts and ends at the two cells furthest apart, wherever they are"),
+        )
+        .arg(
+            Arg::with_name("distances")
+                .long("distances")
+                .help("Colors cells by their distance from the start"),
+        )
+        .arg(
+            Arg::with_name("cell-size")
+                .long("cell-size")
+                .takes_value(true)
+                .default_value("30"),
+        )
+        .arg(
+            Arg::with_name("wall-thickness")
+                .long("wall-thickness")
+                .takes_value(true)
+                .default_value("5"),
+        )
+        .arg(
+            Arg::with_name("background")
+                .long("background")
+                .takes_value(true)
+                .help("Colors are given as rrggbb, with or without a leading #"),
+        )
+        .arg(Arg::with_name("wall-color").long("wall-color").takes_value(true))
+        .arg(Arg::with_name("solution-color").long("solution-color").takes_value(true))
+}
+
+fn parse_color(s: &str) -> Result<[u8; 3], String> {
+    let hex = s.trim_start_matches('#');
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok([r, g, b]),
+        _ => Err(format!("invalid color '{}', expected rrggbb", s)),
+    }
+}
+
+fn parse_size(s: &str, shape: Shape) -> Result<(usize, usize), String> {
+    let error = || match shape {
+        Shape::Square => format!("invalid size '{}', expected WIDTHxHEIGHT", s),
+        Shape::Polar => format!("invalid size '{}', expected a number of rows", s),
+    };
+    let parts = s.split('x')
+        .map(|n| n.trim().parse::<usize>().map_err(|_| error()))
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    match (shape, parts.as_slice()) {
+        (_, [n]) if *n > 0 => Ok((*n, *n)),
+        (Shape::Square, [w, h]) if *w > 0 && *h > 0 => Ok((*w, *h)),
+        _ => Err(error()),
+    }
+}
+
+fn parse_value<T: FromStr>(matches: &ArgMatches, name: &str) -> Result<T, String> {
+    let value = matches.value_of(name).unwrap();
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for --{}", value, name))
+}
+
+fn parse(matches: &ArgMatches) -> Result<Options, String> {
+    let shape = match matches.value_of("grid") {
+        Some("polar") => Shape::Polar,
+        _ => Shape::Square,
+    };
+    let format = match matches.value_of("format") {
+        Some("ascii") => Format::Ascii,
+        _ => Format::Png,
+    };
+    let (width, height) = parse_size(matches.value_of("size").unwrap(), shape)?;
+    let algorithm = Algorithm::from_str(matches.value_of("algorithm").unwrap())?;
+
+    if shape == Shape::Polar && !algorithm.is_generic() {
+        return Err(format!("{} can't generate polar grids", algorithm));
+    }
+
+    if shape == Shape::Polar && format == Format::Ascii {
+        return Err("polar grids can only be rendered as png".to_owned());
+    }
+
+    let seed = match matches.value_of("seed") {
+        Some(_) => parse_value(matches, "seed")?,
+        None => rand::thread_rng().gen(),
+    };
+
+    let braid: f32 = parse_value(matches, "braid")?;
+    if braid < 0.0 || braid > 1.0 {
+        return Err(format!("braid probability {} is not between 0 and 1", braid));
+    }
+
+    let mut style = StyleBuilder::new()
+        .cell_size(parse_value(matches, "cell-size")?)
+        .wall_thickness(parse_value(matches, "wall-thickness")?);
+    if let Some(color) = matches.value_of("background") {
+        style = style.background_color(&parse_color(color)?);
+    }
+    if let Some(color) = matches.value_of("wall-color") {
+        style = style.wall_color(&parse_color(color)?);
+    }
+    if let Some(color) = matches.value_of("solution-color") {
+        style = style.solution_color(&parse_color(color)?);
+    }
+
+    let solution = matches.is_present("solution");
+    let distances = matches.is_present("distances");
+    // an ascii cell only has room for either its distance or the path
+    if solution && distances && format == Format::Ascii {
+        return Err("ascii output can't show both the solution and distances".to_owned());
+    }
+    if solution {
+        style = style.draw_solution();
+    }
+    if distances {
+        style = style.color_fn(default_color_fn);
+    }
+
+    Ok(Options {
+        shape,
+        width,
+        height,
+        algorithm,
+        seed,
+        braid,
+        format,
+        output: matches.value_of("output").map(|o| o.to_owned()),
+        solution,
+        distances,
+        hardest: matches.is_present("hardest"),
+        style,
+    })
+}
+
+// to_string has no notion of a solution, so the path is drawn over the
+// blank cells afterwards
+fn ascii(grid: &Grid, options: &Options) -> String {
+    let mut lines = grid.to_string(options.distances)
+        .lines()
+        .map(|l| l.to_owned())
+        .collect::<Vec<String>>();
+
+    if options.solution {
+        for cell in grid.cells().iter().filter(|c| c.in_solution()) {
+            let col = cell.pos.col * 4 + 1;
+            lines[cell.pos.row * 2 + 1].replace_range(col..col + 3, " * ");
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+fn save(img: RgbImage, output: Option<String>) -> Result<(), String> {
+    let name = output.unwrap_or_else(|| "maze.png".to_owned());
+    img.save(&name)
+        .map_err(|e| format!("couldn't write {}: {}", name, e))
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let style = options.style.build();
+    let output = options.output.clone();
+
+    match options.shape {
+        Shape::Square => {
+            let mut grid = Grid::new(options.width, options.height);
+            options.algorithm.generate(&mut grid, &mut rng);
+            braid(&mut grid, options.braid, &mut rng);
+
+            let hardest = if options.hardest { longest_path(&grid) } else { None };
+            let (start, end) = match hardest {
+                Some(path) => (path.start().clone(), path.end().clone()),
+                None => furthest_corners(&mut grid),
+            };
+            if options.solution {
+                solve(&mut grid, &start, &end);
+            } else {
+                dijkstra(&mut grid, &start);
+            }
+
+            match options.format {
+                Format::Png => save(grid_image(&grid, &style), output)?,
+                Format::Ascii => {
+                    let text = ascii(&grid, options);
+                    match output {
+                        Some(name) => File::create(&name)
+                            .and_then(|mut f| f.write_all(text.as_bytes()))
+                            .map_err(|e| format!("couldn't write {}: {}", name, e))?,
+                        None => print!("{}", text),
+                    }
+                }
+            }
+        }
+        Shape::Polar => {
+            let mut grid = PolarGrid::new(options.height);
+            options.algorithm.generate_generic(&mut grid, &mut rng)?;
+            braid(&mut grid, options.braid, &mut rng);
+
+            let hardest = if options.hardest { longest_path(&grid) } else { None };
+            let (start, end) = match hardest {
+                Some(path) => (path.start().clone(), path.end().clone()),
+                None => {
+                    let center = Position::new(0, 0);
+                    let rim = furthest_on_rim(&mut grid, &center);
+                    (center, rim)
+                }
+            };
+            if options.solution {
+                solve(&mut grid, &start, &end);
+            } else {
+                dijkstra(&mut grid, &start);
+            }
+
+            save(polar_image(&grid, &style), output)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let matches = app().get_matches();
+
+    if matches.is_present("list") {
+        for algorithm in Algorithm::all() {
+            let grids = if algorithm.is_generic() { "square, polar" } else { "square" };
+            println!("{:<24}{}", algorithm.name(), grids);
+        }
+        return;
+    }
+
+    let result = parse(&matches).and_then(|options| {
+        if !matches.is_present("seed") {
+            eprintln!("seed: {}", options.seed);
+        }
+        run(&options)
+    });
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test_meanderer {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#ff8000"), Ok([255, 128, 0]));
+        assert_eq!(parse_color("0a0B0c"), Ok([10, 11, 12]));
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("gg0000").is_err());
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("20x10", Shape::Square), Ok((20, 10)));
+        assert_eq!(parse_size("12", Shape::Square), Ok((12, 12)));
+        assert_eq!(parse_size("12", Shape::Polar), Ok((12, 12)));
+        assert!(parse_size("20x10", Shape::Polar).is_err());
+        assert!(parse_size("0x10", Shape::Square).is_err());
+        assert!(parse_size("wide", Shape::Square).is_err());
+    }
+
+    #[test]
+    fn polar_needs_a_generic_algorithm() {
+        let args = ["meanderer", "--grid", "polar", "--size", "5", "-a", "sidewinder"];
+        let matches = app().get_matches_from(args.iter());
+        assert!(parse(&matches).is_err());
+
+        let args = ["meanderer", "--grid", "polar", "--size", "5", "-a", "wilsons"];
+        let matches = app().get_matches_from(args.iter());
+        let options = parse(&matches).unwrap();
+        assert_eq!(options.algorithm, Algorithm::Wilsons);
+        assert_eq!(options.height, 5);
+    }
+
+    #[test]
+    fn defaults() {
+        for grid in &["square", "polar"] {
+            let args = ["meanderer", "--grid", grid];
+            let matches = app().get_matches_from(args.iter());
+            let options = parse(&matches).unwrap();
+            assert_eq!((options.width, options.height), (20, 20));
+        }
+    }
+
+    #[test]
+    fn ascii_shows_solution_or_distances() {
+        let args = ["meanderer", "-f", "ascii", "--solution", "--distances"];
+        let matches = app().get_matches_from(args.iter());
+        assert!(parse(&matches).is_err());
+
+        let args = ["meanderer", "-f", "png", "--solution", "--distances"];
+        let matches = app().get_matches_from(args.iter());
+        assert!(parse(&matches).is_ok());
+    }
+}
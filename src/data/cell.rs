@@ -1,4 +1,4 @@
-use data::pos::{MazePosition, Position};
+use data::pos::{MazePosition, Position, Position3D};
 use std::collections::BTreeSet;
 use std::hash::{Hash, Hasher};
 
@@ -25,18 +25,31 @@ pub trait MazeCell: Clone + Eq + Hash + PartialEq {
 
     fn links(&self) -> &BTreeSet<Self::PositionType>;
 
+    // drops every link from this cell only, so the cells on the other ends
+    // need clearing too
+    fn clear_links(&mut self);
+
     fn neighbors(&self) -> Vec<Self::PositionType>;
 
     fn weight(&self) -> u32;
 
     fn update_weight(&mut self, weight: u32);
 
+    // what it takes to step into this cell. Every cell starts at 1, so the
+    // distances found by dijkstra are a count of steps until costs are raised
+    fn cost(&self) -> u32;
+
+    fn update_cost(&mut self, cost: u32);
+
     fn in_solution(&self) -> bool;
 
     fn mark_in_solution(&mut self);
+
+    fn clear_solution(&mut self);
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Cell {
     pub pos: Position,
     pub north: Option<Position>,
@@ -45,6 +58,7 @@ pub struct Cell {
     pub west: Option<Position>,
 
     weight: u32,
+    cost: u32,
     in_solution: bool,
     links: BTreeSet<Position>,
 }
@@ -54,6 +68,7 @@ impl Cell {
         Cell {
             pos: Position::new(row, col),
             weight: 0,
+            cost: 1,
             in_solution: false,
             north: None,
             south: None,
@@ -83,6 +98,10 @@ impl MazeCell for Cell {
         &self.links
     }
 
+    fn clear_links(&mut self) {
+        self.links.clear();
+    }
+
     fn neighbors(&self) -> Vec<Position> {
         let mut neighbors = Vec::new();
 
@@ -113,6 +132,14 @@ impl MazeCell for Cell {
         self.weight = weight;
     }
 
+    fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    fn update_cost(&mut self, cost: u32) {
+        self.cost = cost;
+    }
+
     fn in_solution(&self) -> bool {
         self.in_solution
     }
@@ -120,6 +147,10 @@ impl MazeCell for Cell {
     fn mark_in_solution(&mut self) {
         self.in_solution = true;
     }
+
+    fn clear_solution(&mut self) {
+        self.in_solution = false;
+    }
 }
 
 impl Hash for Cell {
@@ -137,6 +168,7 @@ impl PartialEq for Cell {
 impl Eq for Cell {}
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct PolarCell {
     pub pos: Position,
     pub ccw: Option<Position>,
@@ -145,6 +177,7 @@ pub struct PolarCell {
     pub outward: Vec<Position>,
 
     weight: u32,
+    cost: u32,
     in_solution: bool,
     links: BTreeSet<Position>,
 }
@@ -154,6 +187,7 @@ impl PolarCell {
         PolarCell {
             pos: Position::new(row, col),
             weight: 0,
+            cost: 1,
             in_solution: false,
             ccw: None,
             cw: None,
@@ -183,6 +217,10 @@ impl MazeCell for PolarCell {
         &self.links
     }
 
+    fn clear_links(&mut self) {
+        self.links.clear();
+    }
+
     fn neighbors(&self) -> Vec<Position> {
         let mut neighbors = Vec::new();
 
@@ -213,6 +251,14 @@ impl MazeCell for PolarCell {
         self.weight = weight;
     }
 
+    fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    fn update_cost(&mut self, cost: u32) {
+        self.cost = cost;
+    }
+
     fn in_solution(&self) -> bool {
         self.in_solution
     }
@@ -220,6 +266,10 @@ impl MazeCell for PolarCell {
     fn mark_in_solution(&mut self) {
         self.in_solution = true;
     }
+
+    fn clear_solution(&mut self) {
+        self.in_solution = false;
+    }
 }
 
 impl Hash for PolarCell {
@@ -236,6 +286,390 @@ impl PartialEq for PolarCell {
 
 impl Eq for PolarCell {}
 
+#[derive(Debug, Clone)]
+pub struct HexCell {
+    pub pos: Position,
+    pub north: Option<Position>,
+    pub south: Option<Position>,
+    pub northeast: Option<Position>,
+    pub northwest: Option<Position>,
+    pub southeast: Option<Position>,
+    pub southwest: Option<Position>,
+
+    weight: u32,
+    cost: u32,
+    in_solution: bool,
+    links: BTreeSet<Position>,
+}
+
+impl HexCell {
+    pub fn new(row: usize, col: usize) -> Self {
+        HexCell {
+            pos: Position::new(row, col),
+            weight: 0,
+            cost: 1,
+            in_solution: false,
+            north: None,
+            south: None,
+            northeast: None,
+            northwest: None,
+            southeast: None,
+            southwest: None,
+            links: BTreeSet::new(),
+        }
+    }
+}
+
+impl MazeCell for HexCell {
+    type PositionType = Position;
+
+    fn pos(&self) -> &Position {
+        &self.pos
+    }
+
+    fn link(&mut self, other: &Position) {
+        self.links.insert(other.clone());
+    }
+
+    fn unlink(&mut self, other: &Position) {
+        self.links.remove(other);
+    }
+
+    fn links(&self) -> &BTreeSet<Position> {
+        &self.links
+    }
+
+    fn clear_links(&mut self) {
+        self.links.clear();
+    }
+
+    fn neighbors(&self) -> Vec<Position> {
+        let mut neighbors = Vec::new();
+
+        if let Some(ref pos) = self.north {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.south {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.northeast {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.northwest {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.southeast {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.southwest {
+            neighbors.push(pos.clone());
+        }
+
+        neighbors
+    }
+
+    fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    fn update_weight(&mut self, weight: u32) {
+        self.weight = weight;
+    }
+
+    fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    fn update_cost(&mut self, cost: u32) {
+        self.cost = cost;
+    }
+
+    fn in_solution(&self) -> bool {
+        self.in_solution
+    }
+
+    fn mark_in_solution(&mut self) {
+        self.in_solution = true;
+    }
+
+    fn clear_solution(&mut self) {
+        self.in_solution = false;
+    }
+}
+
+impl Hash for HexCell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pos.hash(state);
+    }
+}
+
+impl PartialEq for HexCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.pos == other.pos
+    }
+}
+
+impl Eq for HexCell {}
+
+#[derive(Debug, Clone)]
+pub struct TriangleCell {
+    pub pos: Position,
+    pub north: Option<Position>,
+    pub south: Option<Position>,
+    pub east: Option<Position>,
+    pub west: Option<Position>,
+
+    weight: u32,
+    cost: u32,
+    in_solution: bool,
+    links: BTreeSet<Position>,
+}
+
+impl TriangleCell {
+    pub fn new(row: usize, col: usize) -> Self {
+        TriangleCell {
+            pos: Position::new(row, col),
+            weight: 0,
+            cost: 1,
+            in_solution: false,
+            north: None,
+            south: None,
+            east: None,
+            west: None,
+            links: BTreeSet::new(),
+        }
+    }
+
+    // upright cells have their apex pointing north, so they can only have a
+    // neighbor to the south, and inverted cells only one to the north
+    pub fn upright(&self) -> bool {
+        (self.pos.row + self.pos.col) % 2 == 0
+    }
+}
+
+impl MazeCell for TriangleCell {
+    type PositionType = Position;
+
+    fn pos(&self) -> &Position {
+        &self.pos
+    }
+
+    fn link(&mut self, other: &Position) {
+        self.links.insert(other.clone());
+    }
+
+    fn unlink(&mut self, other: &Position) {
+        self.links.remove(other);
+    }
+
+    fn links(&self) -> &BTreeSet<Position> {
+        &self.links
+    }
+
+    fn clear_links(&mut self) {
+        self.links.clear();
+    }
+
+    fn neighbors(&self) -> Vec<Position> {
+        let mut neighbors = Vec::new();
+
+        if let Some(ref pos) = self.north {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.south {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.east {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.west {
+            neighbors.push(pos.clone());
+        }
+
+        neighbors
+    }
+
+    fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    fn update_weight(&mut self, weight: u32) {
+        self.weight = weight;
+    }
+
+    fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    fn update_cost(&mut self, cost: u32) {
+        self.cost = cost;
+    }
+
+    fn in_solution(&self) -> bool {
+        self.in_solution
+    }
+
+    fn mark_in_solution(&mut self) {
+        self.in_solution = true;
+    }
+
+    fn clear_solution(&mut self) {
+        self.in_solution = false;
+    }
+}
+
+impl Hash for TriangleCell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pos.hash(state);
+    }
+}
+
+impl PartialEq for TriangleCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.pos == other.pos
+    }
+}
+
+impl Eq for TriangleCell {}
+
+#[derive(Debug, Clone)]
+pub struct Cell3D {
+    pub pos: Position3D,
+    pub north: Option<Position3D>,
+    pub south: Option<Position3D>,
+    pub east: Option<Position3D>,
+    pub west: Option<Position3D>,
+    pub up: Option<Position3D>,
+    pub down: Option<Position3D>,
+
+    weight: u32,
+    cost: u32,
+    in_solution: bool,
+    links: BTreeSet<Position3D>,
+}
+
+impl Cell3D {
+    pub fn new(level: usize, row: usize, col: usize) -> Self {
+        Cell3D {
+            pos: Position3D::new(level, row, col),
+            weight: 0,
+            cost: 1,
+            in_solution: false,
+            north: None,
+            south: None,
+            east: None,
+            west: None,
+            up: None,
+            down: None,
+            links: BTreeSet::new(),
+        }
+    }
+}
+
+impl MazeCell for Cell3D {
+    type PositionType = Position3D;
+
+    fn pos(&self) -> &Position3D {
+        &self.pos
+    }
+
+    fn link(&mut self, other: &Position3D) {
+        self.links.insert(other.clone());
+    }
+
+    fn unlink(&mut self, other: &Position3D) {
+        self.links.remove(other);
+    }
+
+    fn links(&self) -> &BTreeSet<Position3D> {
+        &self.links
+    }
+
+    fn clear_links(&mut self) {
+        self.links.clear();
+    }
+
+    fn neighbors(&self) -> Vec<Position3D> {
+        let mut neighbors = Vec::new();
+
+        if let Some(ref pos) = self.north {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.south {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.east {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.west {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.up {
+            neighbors.push(pos.clone());
+        }
+
+        if let Some(ref pos) = self.down {
+            neighbors.push(pos.clone());
+        }
+
+        neighbors
+    }
+
+    fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    fn update_weight(&mut self, weight: u32) {
+        self.weight = weight;
+    }
+
+    fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    fn update_cost(&mut self, cost: u32) {
+        self.cost = cost;
+    }
+
+    fn in_solution(&self) -> bool {
+        self.in_solution
+    }
+
+    fn mark_in_solution(&mut self) {
+        self.in_solution = true;
+    }
+
+    fn clear_solution(&mut self) {
+        self.in_solution = false;
+    }
+}
+
+impl Hash for Cell3D {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pos.hash(state);
+    }
+}
+
+impl PartialEq for Cell3D {
+    fn eq(&self, other: &Self) -> bool {
+        self.pos == other.pos
+    }
+}
+
+impl Eq for Cell3D {}
+
 #[cfg(test)]
 mod test_cell {
     use super::*;
@@ -246,6 +680,7 @@ mod test_cell {
         let b = Cell {
             pos: Position::new(1, 2),
             weight: 0,
+            cost: 1,
             in_solution: false,
             north: None,
             south: None,
@@ -262,6 +697,7 @@ mod test_cell {
         let a = Cell {
             pos: Position::new(10, 20),
             weight: 1,
+            cost: 1,
             in_solution: false,
             north: None,
             south: None,
@@ -273,6 +709,7 @@ mod test_cell {
         let b = Cell {
             pos: Position::new(10, 20),
             weight: 2,
+            cost: 1,
             in_solution: true,
             north: None,
             south: None,
@@ -284,6 +721,7 @@ mod test_cell {
         let c = Cell {
             pos: Position::new(30, 40),
             weight: 1,
+            cost: 1,
             in_solution: true,
             north: None,
             south: None,
@@ -393,6 +831,20 @@ mod test_cell {
         assert!(!a.is_linked(&b));
         assert!(!a.is_linked_pos(&b.pos));
     }
+
+    #[test]
+    fn clearing() {
+        let mut a = Cell::new(0, 0);
+        a.link(&Position::new(0, 1));
+        a.link(&Position::new(1, 0));
+        a.mark_in_solution();
+
+        a.clear_links();
+        a.clear_solution();
+
+        assert!(a.links().is_empty());
+        assert!(!a.in_solution());
+    }
 }
 
 #[cfg(test)]
@@ -405,6 +857,7 @@ mod test_polar_cell {
         let b = PolarCell {
             pos: Position::new(1, 2),
             weight: 0,
+            cost: 1,
             in_solution: false,
             ccw: None,
             cw: None,
@@ -421,6 +874,7 @@ mod test_polar_cell {
         let a = PolarCell {
             pos: Position::new(10, 20),
             weight: 1,
+            cost: 1,
             in_solution: false,
             ccw: None,
             cw: None,
@@ -432,6 +886,7 @@ mod test_polar_cell {
         let b = PolarCell {
             pos: Position::new(10, 20),
             weight: 2,
+            cost: 1,
             in_solution: true,
             ccw: None,
             cw: None,
@@ -443,6 +898,7 @@ mod test_polar_cell {
         let c = PolarCell {
             pos: Position::new(30, 40),
             weight: 1,
+            cost: 1,
             in_solution: true,
             ccw: None,
             cw: None,
@@ -553,3 +1009,165 @@ mod test_polar_cell {
         assert!(!a.is_linked_pos(&b.pos));
     }
 }
+
+#[cfg(test)]
+mod test_hex_cell {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let a = HexCell::new(1, 2);
+        assert_eq!(a.pos, Position::new(1, 2));
+        assert_eq!(a.weight(), 0);
+        assert!(!a.in_solution());
+        assert!(a.neighbors().is_empty());
+        assert!(a.links().is_empty());
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut a = HexCell::new(3, 4);
+        a.north = Some(Position::new(2, 4));
+        a.south = Some(Position::new(4, 4));
+        a.northeast = Some(Position::new(2, 5));
+        a.northwest = Some(Position::new(2, 3));
+        a.southeast = Some(Position::new(3, 5));
+        a.southwest = Some(Position::new(3, 3));
+
+        assert_eq!(
+            a.neighbors(),
+            vec![
+                Position::new(2, 4),
+                Position::new(4, 4),
+                Position::new(2, 5),
+                Position::new(2, 3),
+                Position::new(3, 5),
+                Position::new(3, 3),
+            ]
+        );
+
+        a.northeast = None;
+        a.southwest = None;
+
+        assert_eq!(
+            a.neighbors(),
+            vec![
+                Position::new(2, 4),
+                Position::new(4, 4),
+                Position::new(2, 3),
+                Position::new(3, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn linking() {
+        let mut a = HexCell::new(10, 20);
+        let b = HexCell::new(30, 40);
+
+        a.link(&b.pos);
+        assert!(a.is_linked(&b));
+        assert!(a.is_linked_pos(&b.pos));
+
+        a.unlink(&b.pos);
+        assert!(!a.is_linked(&b));
+        assert!(!a.is_linked_pos(&b.pos));
+    }
+}
+
+#[cfg(test)]
+mod test_triangle_cell {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let a = TriangleCell::new(1, 2);
+        assert_eq!(a.pos, Position::new(1, 2));
+        assert_eq!(a.weight(), 0);
+        assert!(!a.in_solution());
+        assert!(a.neighbors().is_empty());
+        assert!(a.links().is_empty());
+    }
+
+    #[test]
+    fn upright() {
+        assert!(TriangleCell::new(0, 0).upright());
+        assert!(!TriangleCell::new(0, 1).upright());
+        assert!(!TriangleCell::new(1, 0).upright());
+        assert!(TriangleCell::new(1, 1).upright());
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut a = TriangleCell::new(3, 4);
+        a.south = Some(Position::new(4, 4));
+        a.east = Some(Position::new(3, 5));
+        a.west = Some(Position::new(3, 3));
+
+        assert_eq!(
+            a.neighbors(),
+            vec![
+                Position::new(4, 4),
+                Position::new(3, 5),
+                Position::new(3, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn linking() {
+        let mut a = TriangleCell::new(10, 20);
+        let b = TriangleCell::new(10, 21);
+
+        a.link(&b.pos);
+        assert!(a.is_linked(&b));
+
+        a.unlink(&b.pos);
+        assert!(!a.is_linked(&b));
+    }
+}
+
+#[cfg(test)]
+mod test_cell_3d {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let a = Cell3D::new(1, 2, 3);
+        assert_eq!(a.pos, Position3D::new(1, 2, 3));
+        assert_eq!(a.weight(), 0);
+        assert!(!a.in_solution());
+        assert!(a.neighbors().is_empty());
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut a = Cell3D::new(1, 1, 1);
+        a.north = Some(Position3D::new(1, 0, 1));
+        a.east = Some(Position3D::new(1, 1, 2));
+        a.up = Some(Position3D::new(2, 1, 1));
+        a.down = Some(Position3D::new(0, 1, 1));
+
+        assert_eq!(
+            a.neighbors(),
+            vec![
+                Position3D::new(1, 0, 1),
+                Position3D::new(1, 1, 2),
+                Position3D::new(2, 1, 1),
+                Position3D::new(0, 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn linking() {
+        let mut a = Cell3D::new(0, 0, 0);
+        let b = Cell3D::new(1, 0, 0);
+
+        a.link(&b.pos);
+        assert!(a.is_linked(&b));
+
+        a.unlink(&b.pos);
+        assert!(!a.is_linked(&b));
+    }
+}
@@ -1,11 +1,13 @@
-use data::cell::{Cell, MazeCell, PolarCell};
-use data::pos::Position;
-use itertools::Itertools;
+use data::cell::{Cell, Cell3D, HexCell, MazeCell, PolarCell, TriangleCell};
+use data::mask::Mask;
+use data::pos::{Position, Position3D};
 use rand;
 use rand::Rng;
 use std::collections::BTreeSet;
+use std::error::Error;
 use std::f32::consts::PI;
-use std::iter;
+use std::fmt;
+use std::str::FromStr;
 
 pub trait MazeGrid {
     type CellType: MazeCell;
@@ -26,7 +28,7 @@ pub trait MazeGrid {
         pos: &<Self::CellType as MazeCell>::PositionType,
     ) -> Vec<<Self::CellType as MazeCell>::PositionType> {
         match self.get(pos) {
-            Some(ref cell) => cell.neighbors(),
+            Some(cell) => cell.neighbors(),
             None => Vec::new(),
         }
     }
@@ -35,15 +37,18 @@ pub trait MazeGrid {
         &self,
         pos: &<Self::CellType as MazeCell>::PositionType,
     ) -> Option<<Self::CellType as MazeCell>::PositionType> {
-        self.get(pos).and_then(|cell| Some(cell.pos().clone()))
+        self.get(pos).map(|cell| cell.pos().clone())
     }
 
     fn random_pos(&self) -> Option<<Self::CellType as MazeCell>::PositionType> {
-        let mut rng = rand::thread_rng();
-        match rng.choose(self.cells()) {
-            Some(cell) => Some(cell.pos().clone()),
-            None => None,
-        }
+        self.random_pos_with_rng(&mut rand::thread_rng())
+    }
+
+    fn random_pos_with_rng<R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> Option<<Self::CellType as MazeCell>::PositionType> {
+        rng.choose(self.cells()).map(|cell| cell.pos().clone())
     }
 
     fn link(
@@ -52,11 +57,11 @@ pub trait MazeGrid {
         other: &<Self::CellType as MazeCell>::PositionType,
     ) {
         {
-            let ref mut root = self.get_mut(pos).unwrap();
+            let root = self.get_mut(pos).unwrap();
             root.link(other);
         }
         {
-            let ref mut root = self.get_mut(other).unwrap();
+            let root = self.get_mut(other).unwrap();
             root.link(pos);
         }
     }
@@ -67,11 +72,11 @@ pub trait MazeGrid {
         other: &<Self::CellType as MazeCell>::PositionType,
     ) {
         {
-            let ref mut root = self.get_mut(pos).unwrap();
+            let root = self.get_mut(pos).unwrap();
             root.unlink(other);
         }
         {
-            let ref mut root = self.get_mut(other).unwrap();
+            let root = self.get_mut(other).unwrap();
             root.unlink(pos);
         }
     }
@@ -102,41 +107,102 @@ pub trait MazeGrid {
         }
     }
 
+    // the positions of every cell, for changing them all through get_mut
+    fn positions(&self) -> Vec<<Self::CellType as MazeCell>::PositionType> {
+        self.cells().iter().map(|c| c.pos().clone()).collect()
+    }
+
+    // back to a grid with no passages, ready for another generator to run
+    // on without reallocating
+    fn reset_links(&mut self) {
+        for pos in self.positions() {
+            if let Some(cell) = self.get_mut(&pos) {
+                cell.clear_links();
+            }
+        }
+    }
+
+    fn clear_weights(&mut self) {
+        for pos in self.positions() {
+            if let Some(cell) = self.get_mut(&pos) {
+                cell.update_weight(0);
+            }
+        }
+    }
+
+    fn clear_solution(&mut self) {
+        for pos in self.positions() {
+            if let Some(cell) = self.get_mut(&pos) {
+                cell.clear_solution();
+            }
+        }
+    }
+
     fn to_string(&self, display_labels: bool) -> String;
 }
 
+// Plane grids have solid borders, cylinders wrap east to west and tori wrap
+// both east to west and north to south. An axis only wraps when it is more
+// than two cells long, otherwise the wrapped neighbor would already be a
+// neighbor on the other side
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialization", serde(rename_all = "lowercase"))]
+pub enum Topology {
+    Plane,
+    Cylinder,
+    Torus,
+}
+
 #[derive(Debug, Clone)]
 pub struct Grid {
     pub width: usize,
     pub height: usize,
+    pub topology: Topology,
     pub cells: Vec<Cell>,
 }
 
 impl Grid {
     pub fn new(width: usize, height: usize) -> Self {
+        Grid::with_topology(width, height, Topology::Plane)
+    }
+
+    pub fn with_topology(width: usize, height: usize, topology: Topology) -> Self {
         let mut grid = Grid {
-            width: width,
-            height: height,
+            width,
+            height,
+            topology,
             cells: Vec::with_capacity(width * height),
         };
 
+        let wrap_cols = topology != Topology::Plane && width > 2;
+        let wrap_rows = topology == Topology::Torus && height > 2;
+
         for row in 0..height {
             for col in 0..width {
                 let mut new = Cell::new(row, col);
                 if row < height - 1 {
                     new.south = Some(Position::new(row + 1, col))
+                } else if wrap_rows {
+                    new.south = Some(Position::new(0, col))
                 }
 
                 if row > 0 {
                     new.north = Some(Position::new(row - 1, col))
+                } else if wrap_rows {
+                    new.north = Some(Position::new(height - 1, col))
                 }
 
                 if col > 0 {
                     new.west = Some(Position::new(row, col - 1))
+                } else if wrap_cols {
+                    new.west = Some(Position::new(row, width - 1))
                 }
 
                 if col < width - 1 {
                     new.east = Some(Position::new(row, col + 1))
+                } else if wrap_cols {
+                    new.east = Some(Position::new(row, 0))
                 }
 
                 grid.cells.push(new);
@@ -176,41 +242,52 @@ impl MazeGrid for Grid {
     }
 
     fn to_string(&self, display_labels: bool) -> String {
-        let mut output = String::new();
-        output += &iter::repeat("+").take(self.width + 1).join("---");
+        // passages across a wrapped seam show up as gaps in the border
+        let linked = |cell: &Cell, other: &Option<Position>| match *other {
+            Some(ref pos) => cell.is_linked_pos(pos),
+            None => false,
+        };
+
+        let mut output = "+".to_string();
+        for col in 0..self.width {
+            let cell = self.get(&Position::new(0, col)).unwrap();
+            if linked(cell, &cell.north) {
+                output += "   +";
+            } else {
+                output += "---+";
+            }
+        }
         output += "\n";
 
         for row in 0..self.height {
-            let mut top = "|".to_string();
+            let wrapped = match self.get(&Position::new(row, 0)) {
+                Some(first) => linked(first, &first.west),
+                None => false,
+            };
+            let mut top = if wrapped {
+                " ".to_string()
+            } else {
+                "|".to_string()
+            };
             let mut bot = "+".to_string();
 
             for col in 0..self.width {
                 let cur = Position::new(row, col);
-                let ref cell = self.get(&cur).unwrap();
+                let cell = self.get(&cur).unwrap();
                 if display_labels {
                     top += &format!("{:^3}", cell.label());
                 } else {
                     top += "   ";
                 }
 
-                if col < self.width - 1 {
-                    let east = self.get(&Position::new(cur.row, cur.col + 1)).unwrap();
-                    if cell.is_linked(east) {
-                        top += " ";
-                    } else {
-                        top += "|";
-                    }
+                if linked(cell, &cell.east) {
+                    top += " ";
                 } else {
                     top += "|";
                 }
 
-                if row < self.height - 1 {
-                    let south = self.get(&Position::new(cur.row + 1, cur.col)).unwrap();
-                    if cell.is_linked(south) {
-                        bot += "   +";
-                    } else {
-                        bot += "---+";
-                    }
+                if linked(cell, &cell.south) {
+                    bot += "   +";
                 } else {
                     bot += "---+";
                 }
@@ -226,6 +303,160 @@ impl MazeGrid for Grid {
     }
 }
 
+// lines and columns start at 1, like a text editor would show them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGridError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseGridError {
+    fn new(line: usize, column: usize, message: &str) -> Self {
+        ParseGridError {
+            line: line + 1,
+            column: column + 1,
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseGridError {}
+
+// reads back the output of to_string, with or without labels. Numeric labels
+// are restored as weights and anything else inside a cell is ignored. Gaps in
+// the outer border are passages across a wrapped seam, so a grid only comes
+// back as a cylinder or torus when one of its seams has been opened
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+
+        let length = match lines.first() {
+            Some(line) if line.len() >= 5 && (line.len() - 1) % 4 == 0 => line.len(),
+            Some(line) => {
+                return Err(ParseGridError::new(
+                    0,
+                    line.len(),
+                    "the top border should be a + followed by ---+ for every column",
+                ))
+            }
+            None => return Err(ParseGridError::new(0, 0, "the maze is empty")),
+        };
+        let width = (length - 1) / 4;
+
+        if lines.len() < 3 || lines.len() % 2 == 0 {
+            return Err(ParseGridError::new(
+                lines.len(),
+                0,
+                "every row of cells should be followed by a border",
+            ));
+        }
+        let height = (lines.len() - 1) / 2;
+
+        for (i, line) in lines.iter().enumerate() {
+            if line.len() != length {
+                let message = format!("expected {} characters but found {}", length, line.len());
+                return Err(ParseGridError::new(i, line.len().min(length), &message));
+            }
+
+            for col in 0..width + 1 {
+                let x = col * 4;
+                if i % 2 == 0 {
+                    if line[x] != '+' {
+                        return Err(ParseGridError::new(i, x, "expected a +"));
+                    }
+                    if col < width {
+                        let wall = &line[x + 1..x + 4];
+                        if wall != ['-'; 3] && wall != [' '; 3] {
+                            return Err(ParseGridError::new(i, x + 1, "expected --- or spaces"));
+                        }
+                    }
+                } else if line[x] != '|' && line[x] != ' ' {
+                    return Err(ParseGridError::new(i, x, "expected a | or a space"));
+                }
+            }
+        }
+
+        let last = lines.len() - 1;
+        let open_south = |row: usize, col: usize| lines[row * 2 + 2][col * 4 + 1] == ' ';
+        let open_east = |row: usize, col: usize| lines[row * 2 + 1][col * 4 + 4] == ' ';
+
+        // both sides of a seam have to agree before it can be opened
+        let mut wrap_rows = false;
+        for col in 0..width {
+            if lines[0][col * 4 + 1] != lines[last][col * 4 + 1] {
+                let message = "the bottom border doesn't match the top one";
+                return Err(ParseGridError::new(last, col * 4 + 1, message));
+            }
+            wrap_rows |= open_south(height - 1, col);
+        }
+
+        let mut wrap_cols = false;
+        for row in 0..height {
+            if lines[row * 2 + 1][0] != lines[row * 2 + 1][length - 1] {
+                let message = "the right border doesn't match the left one";
+                return Err(ParseGridError::new(row * 2 + 1, length - 1, message));
+            }
+            wrap_cols |= open_east(row, width - 1);
+        }
+
+        if wrap_rows && height <= 2 {
+            let message = "passages can only wrap around grids more than two rows tall";
+            return Err(ParseGridError::new(0, 0, message));
+        }
+        if wrap_cols && width <= 2 {
+            let message = "passages can only wrap around grids more than two columns wide";
+            return Err(ParseGridError::new(1, 0, message));
+        }
+
+        let topology = if wrap_rows {
+            Topology::Torus
+        } else if wrap_cols {
+            Topology::Cylinder
+        } else {
+            Topology::Plane
+        };
+        let mut grid = Grid::with_topology(width, height, topology);
+
+        for row in 0..height {
+            for col in 0..width {
+                let pos = Position::new(row, col);
+                let label = lines[row * 2 + 1][col * 4 + 1..col * 4 + 4]
+                    .iter()
+                    .collect::<String>();
+
+                let (east, south) = {
+                    let cell = grid.get_mut(&pos).unwrap();
+                    if let Ok(weight) = label.trim().parse() {
+                        cell.update_weight(weight);
+                    }
+                    (cell.east.clone(), cell.south.clone())
+                };
+
+                if let Some(east) = east.filter(|_| open_east(row, col)) {
+                    grid.link(&pos, &east);
+                }
+
+                if let Some(south) = south.filter(|_| open_south(row, col)) {
+                    grid.link(&pos, &south);
+                }
+            }
+        }
+
+        Ok(grid)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PolarGrid {
     // like with the row-major stuff, i want to store the number of columns
@@ -246,7 +477,7 @@ pub struct PolarGrid {
 impl PolarGrid {
     pub fn new(rows: usize) -> Self {
         let mut grid = PolarGrid {
-            rows: rows,
+            rows,
             cells: Vec::new(),
             row_offsets: Vec::with_capacity(rows),
             column_counts: Vec::with_capacity(rows),
@@ -274,87 +505,633 @@ impl PolarGrid {
             let ratio = (est_cell_width / row_height).round() as usize;
             let num_cols = ratio * self.column_counts[row - 1];
 
-            let previous_offset = self.row_offsets[row - 1];
-            self.row_offsets.push(previous_offset + prev_cols);
-            self.column_counts.push(num_cols);
+            let previous_offset = self.row_offsets[row - 1];
+            self.row_offsets.push(previous_offset + prev_cols);
+            self.column_counts.push(num_cols);
+
+            for col in 0..num_cols {
+                self.cells.push(PolarCell::new(row, col));
+            }
+        }
+    }
+
+    fn _set_neighbors(&mut self) {
+        for i in 0..self.cells.len() {
+            let pos = self.cells[i].pos().clone();
+            if pos.row > 0 {
+                let num_cols = self.column_counts[pos.row];
+                let ratio = num_cols / self.column_counts[pos.row - 1];
+                let parent_pos = Position::new(pos.row - 1, pos.col / ratio);
+
+                if let Some(ref mut cell) = self.get_mut(&pos) {
+                    let cw_col = if pos.col == num_cols - 1 {
+                        0
+                    } else {
+                        pos.col + 1
+                    };
+
+                    let ccw_col = if pos.col == 0 {
+                        num_cols - 1
+                    } else {
+                        pos.col - 1
+                    };
+
+                    cell.cw = Some(Position::new(pos.row, cw_col));
+                    cell.ccw = Some(Position::new(pos.row, ccw_col));
+
+                    cell.inward = Some(parent_pos.clone());
+                }
+
+                if let Some(ref mut cell) = self.get_mut(&parent_pos) {
+                    cell.outward.push(pos);
+                }
+            }
+        }
+    }
+}
+
+impl MazeGrid for PolarGrid {
+    type CellType = PolarCell;
+
+    fn cells(&self) -> &Vec<PolarCell> {
+        &self.cells
+    }
+
+    fn get(&self, pos: &Position) -> Option<&PolarCell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let idx = pos.col + self.row_offsets[pos.row];
+        self.cells.get(idx)
+    }
+
+    fn get_mut(&mut self, pos: &Position) -> Option<&mut PolarCell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let idx = pos.col + self.row_offsets[pos.row];
+        self.cells.get_mut(idx)
+    }
+
+    fn contains(&self, pos: &Position) -> bool {
+        // we don't have to check for negative numbers, since usize
+        pos.row < self.rows && pos.col < self.column_counts[pos.row]
+    }
+
+    fn to_string(&self, _: bool) -> String {
+        "to_string is meaningless for polar grids".to_owned()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HexGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<HexCell>,
+}
+
+impl HexGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut grid = HexGrid {
+            width,
+            height,
+            cells: Vec::with_capacity(width * height),
+        };
+
+        for row in 0..height {
+            for col in 0..width {
+                let mut new = HexCell::new(row, col);
+
+                // odd columns are shifted down by half a cell, so the rows
+                // of the diagonal neighbors depend on the column parity
+                let (north_diagonal, south_diagonal) = if col % 2 == 0 {
+                    (row.checked_sub(1), Some(row))
+                } else {
+                    (Some(row), Some(row + 1).filter(|r| *r < height))
+                };
+
+                if row > 0 {
+                    new.north = Some(Position::new(row - 1, col));
+                }
+
+                if row < height - 1 {
+                    new.south = Some(Position::new(row + 1, col));
+                }
+
+                if col > 0 {
+                    new.northwest = north_diagonal.map(|r| Position::new(r, col - 1));
+                    new.southwest = south_diagonal.map(|r| Position::new(r, col - 1));
+                }
+
+                if col < width - 1 {
+                    new.northeast = north_diagonal.map(|r| Position::new(r, col + 1));
+                    new.southeast = south_diagonal.map(|r| Position::new(r, col + 1));
+                }
+
+                grid.cells.push(new);
+            }
+        }
+
+        grid
+    }
+}
+
+impl MazeGrid for HexGrid {
+    type CellType = HexCell;
+
+    fn cells(&self) -> &Vec<HexCell> {
+        &self.cells
+    }
+
+    fn get(&self, pos: &Position) -> Option<&HexCell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let idx = pos.col + pos.row * self.width;
+        self.cells.get(idx)
+    }
+
+    fn get_mut(&mut self, pos: &Position) -> Option<&mut HexCell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let idx = pos.col + pos.row * self.width;
+        self.cells.get_mut(idx)
+    }
+
+    fn contains(&self, pos: &Position) -> bool {
+        // we don't have to check for negative numbers, since usize
+        pos.row < self.height && pos.col < self.width
+    }
+
+    fn to_string(&self, _: bool) -> String {
+        "to_string is meaningless for hex grids".to_owned()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TriangleGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<TriangleCell>,
+}
+
+impl TriangleGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut grid = TriangleGrid {
+            width,
+            height,
+            cells: Vec::with_capacity(width * height),
+        };
+
+        for row in 0..height {
+            for col in 0..width {
+                let mut new = TriangleCell::new(row, col);
+
+                if col > 0 {
+                    new.west = Some(Position::new(row, col - 1));
+                }
+
+                if col < width - 1 {
+                    new.east = Some(Position::new(row, col + 1));
+                }
+
+                if new.upright() {
+                    if row < height - 1 {
+                        new.south = Some(Position::new(row + 1, col));
+                    }
+                } else if row > 0 {
+                    new.north = Some(Position::new(row - 1, col));
+                }
+
+                grid.cells.push(new);
+            }
+        }
+
+        grid
+    }
+}
+
+impl MazeGrid for TriangleGrid {
+    type CellType = TriangleCell;
+
+    fn cells(&self) -> &Vec<TriangleCell> {
+        &self.cells
+    }
+
+    fn get(&self, pos: &Position) -> Option<&TriangleCell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let idx = pos.col + pos.row * self.width;
+        self.cells.get(idx)
+    }
+
+    fn get_mut(&mut self, pos: &Position) -> Option<&mut TriangleCell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let idx = pos.col + pos.row * self.width;
+        self.cells.get_mut(idx)
+    }
+
+    fn contains(&self, pos: &Position) -> bool {
+        // we don't have to check for negative numbers, since usize
+        pos.row < self.height && pos.col < self.width
+    }
+
+    fn to_string(&self, _: bool) -> String {
+        "to_string is meaningless for triangle grids".to_owned()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MaskedGrid {
+    pub width: usize,
+    pub height: usize,
+    pub mask: Mask,
+    pub cells: Vec<Cell>,
+    // row-major index into cells for every position in the mask, None when
+    // the position is disabled
+    indices: Vec<Option<usize>>,
+}
+
+impl MaskedGrid {
+    pub fn new(mask: Mask) -> Self {
+        let width = mask.width;
+        let height = mask.height;
+        let mut grid = MaskedGrid {
+            width,
+            height,
+            cells: Vec::with_capacity(mask.count()),
+            indices: Vec::with_capacity(width * height),
+            mask,
+        };
+
+        for row in 0..height {
+            for col in 0..width {
+                let pos = Position::new(row, col);
+                if !grid.mask.get(&pos) {
+                    grid.indices.push(None);
+                    continue;
+                }
+
+                let mut new = Cell::new(row, col);
+
+                if row > 0 {
+                    new.north = grid._enabled(row - 1, col);
+                }
+
+                if row < height - 1 {
+                    new.south = grid._enabled(row + 1, col);
+                }
+
+                if col > 0 {
+                    new.west = grid._enabled(row, col - 1);
+                }
+
+                if col < width - 1 {
+                    new.east = grid._enabled(row, col + 1);
+                }
+
+                grid.indices.push(Some(grid.cells.len()));
+                grid.cells.push(new);
+            }
+        }
+
+        grid
+    }
+
+    fn _enabled(&self, row: usize, col: usize) -> Option<Position> {
+        let pos = Position::new(row, col);
+        if self.mask.get(&pos) {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+}
+
+impl MazeGrid for MaskedGrid {
+    type CellType = Cell;
+
+    fn cells(&self) -> &Vec<Cell> {
+        &self.cells
+    }
+
+    fn get(&self, pos: &Position) -> Option<&Cell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        match self.indices[pos.col + pos.row * self.width] {
+            Some(idx) => self.cells.get(idx),
+            None => None,
+        }
+    }
+
+    fn get_mut(&mut self, pos: &Position) -> Option<&mut Cell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        match self.indices[pos.col + pos.row * self.width] {
+            Some(idx) => self.cells.get_mut(idx),
+            None => None,
+        }
+    }
+
+    fn contains(&self, pos: &Position) -> bool {
+        self.mask.get(pos)
+    }
+
+    fn to_string(&self, display_labels: bool) -> String {
+        // a wall is drawn between two positions unless both are disabled or
+        // they are linked
+        let wall = |a: Option<&Cell>, b: Option<&Cell>| match (a, b) {
+            (None, None) => false,
+            (Some(a), Some(b)) => !a.is_linked(b),
+            _ => true,
+        };
+
+        let mut output = "+".to_string();
+        for col in 0..self.width {
+            if self.get(&Position::new(0, col)).is_some() {
+                output += "---+";
+            } else {
+                output += "   +";
+            }
+        }
+        output += "\n";
+
+        for row in 0..self.height {
+            let mut top = if self.get(&Position::new(row, 0)).is_some() {
+                "|".to_string()
+            } else {
+                " ".to_string()
+            };
+            let mut bot = "+".to_string();
+
+            for col in 0..self.width {
+                let cell = self.get(&Position::new(row, col));
+                match cell {
+                    Some(cell) if display_labels => top += &format!("{:^3}", cell.label()),
+                    _ => top += "   ",
+                }
+
+                let east = self.get(&Position::new(row, col + 1));
+                top += if wall(cell, east) { "|" } else { " " };
+
+                let south = self.get(&Position::new(row + 1, col));
+                bot += if wall(cell, south) { "---+" } else { "   +" };
+            }
+
+            top += "\n";
+            bot += "\n";
+
+            output += &top;
+            output += &bot;
+        }
+        output
+    }
+}
+
+// steps from a cell to its neighbor in a fixed direction
+type Step = fn(&Cell) -> &Option<Position>;
+
+// A weave grid is a regular grid where a passage may tunnel under a cell to
+// reach the cell two steps away. A tunnel is stored as a link between the two
+// far cells, and is only possible when the middle cell has a straight passage
+// perpendicular to the tunnel. Once dug, the middle cell stops being a
+// neighbor of the cells at either end, so nothing can break into it.
+#[derive(Debug, Clone)]
+pub struct WeaveGrid {
+    pub grid: Grid,
+}
+
+impl WeaveGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        WeaveGrid {
+            grid: Grid::new(width, height),
+        }
+    }
+
+    // the position two steps away through middle, if the passage in middle
+    // allows tunneling under it in the direction given by step
+    fn _tunnel(
+        &self,
+        middle: &Option<Position>,
+        step: Step,
+        vertical: bool,
+    ) -> Option<Position> {
+        let middle = match *middle {
+            Some(ref pos) => self.grid.get(pos)?,
+            None => return None,
+        };
+
+        let (along_a, along_b, across_a, across_b) = if vertical {
+            (&middle.north, &middle.south, &middle.east, &middle.west)
+        } else {
+            (&middle.east, &middle.west, &middle.north, &middle.south)
+        };
+
+        let linked = |p: &Option<Position>| match *p {
+            Some(ref pos) => middle.is_linked_pos(pos),
+            None => false,
+        };
+
+        // the middle cell must have a straight passage across the direction
+        // of travel, and nothing along it
+        if linked(across_a) && linked(across_b) && !linked(along_a) && !linked(along_b) {
+            step(middle).clone()
+        } else {
+            None
+        }
+    }
+
+    // whether a tunnel runs under middle in the given direction, which is
+    // when the cells on either side of it along that direction are linked
+    fn _tunnelled(&self, middle: &Cell, vertical: bool) -> bool {
+        let (a, b) = if vertical {
+            (&middle.north, &middle.south)
+        } else {
+            (&middle.east, &middle.west)
+        };
+
+        match (a, b) {
+            (Some(a), Some(b)) => self.grid.get(a).map_or(false, |c| c.is_linked_pos(b)),
+            _ => false,
+        }
+    }
+}
+
+impl MazeGrid for WeaveGrid {
+    type CellType = Cell;
+
+    fn cells(&self) -> &Vec<Cell> {
+        self.grid.cells()
+    }
+
+    fn get(&self, pos: &Position) -> Option<&Cell> {
+        self.grid.get(pos)
+    }
+
+    fn get_mut(&mut self, pos: &Position) -> Option<&mut Cell> {
+        self.grid.get_mut(pos)
+    }
+
+    fn contains(&self, pos: &Position) -> bool {
+        self.grid.contains(pos)
+    }
+
+    fn neighbors(&self, pos: &Position) -> Vec<Position> {
+        let cell = match self.get(pos) {
+            Some(cell) => cell,
+            None => return Vec::new(),
+        };
+
+        let directions: [(&Option<Position>, Step, bool); 4] = [
+            (&cell.north, |c| &c.north, true),
+            (&cell.south, |c| &c.south, true),
+            (&cell.east, |c| &c.east, false),
+            (&cell.west, |c| &c.west, false),
+        ];
+
+        // cells along a tunnel can't be entered from its ends, or the middle
+        // of the tunnel from them
+        let mut neighbors = directions
+            .iter()
+            .filter_map(|&(side, _, vertical)| {
+                let next = self.get(side.as_ref()?)?;
+                if self._tunnelled(cell, vertical) || self._tunnelled(next, vertical) {
+                    None
+                } else {
+                    Some(next.pos.clone())
+                }
+            })
+            .collect::<Vec<Position>>();
+
+        for &(middle, step, vertical) in directions.iter() {
+            if let Some(target) = self._tunnel(middle, step, vertical) {
+                neighbors.push(target);
+            }
+        }
+
+        neighbors
+    }
+
+    // panics on links that would come up through the middle of a tunnel,
+    // which are the plain neighbors that neighbors leaves out
+    fn link(&mut self, pos: &Position, other: &Position) {
+        let plain = self.get(pos).map_or(false, |c| c.neighbors().contains(other));
+        assert!(
+            !plain || self.neighbors(pos).contains(other),
+            "can't link {:?} and {:?} through a tunnel",
+            pos,
+            other
+        );
+
+        self.grid.link(pos, other);
+    }
+
+    fn to_string(&self, display_labels: bool) -> String {
+        // tunnels have no ascii representation, so they show up as walls
+        self.grid.to_string(display_labels)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid3D {
+    pub levels: usize,
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Cell3D>,
+}
+
+impl Grid3D {
+    pub fn new(levels: usize, width: usize, height: usize) -> Self {
+        let mut grid = Grid3D {
+            levels,
+            width,
+            height,
+            cells: Vec::with_capacity(levels * width * height),
+        };
 
-            for col in 0..num_cols {
-                self.cells.push(PolarCell::new(row, col));
-            }
-        }
-    }
+        for level in 0..levels {
+            for row in 0..height {
+                for col in 0..width {
+                    let mut new = Cell3D::new(level, row, col);
 
-    fn _set_neighbors(&mut self) {
-        for i in 0..self.cells.len() {
-            let pos = self.cells[i].pos().clone();
-            if pos.row > 0 {
-                let num_cols = self.column_counts[pos.row];
-                let ratio = num_cols / self.column_counts[pos.row - 1];
-                let parent_pos = Position::new(pos.row - 1, pos.col / ratio);
+                    if row > 0 {
+                        new.north = Some(Position3D::new(level, row - 1, col));
+                    }
 
-                if let Some(ref mut cell) = self.get_mut(&pos) {
-                    let cw_col = if pos.col == num_cols - 1 {
-                        0
-                    } else {
-                        pos.col + 1
-                    };
+                    if row < height - 1 {
+                        new.south = Some(Position3D::new(level, row + 1, col));
+                    }
 
-                    let ccw_col = if pos.col == 0 {
-                        num_cols - 1
-                    } else {
-                        pos.col - 1
-                    };
+                    if col > 0 {
+                        new.west = Some(Position3D::new(level, row, col - 1));
+                    }
 
-                    cell.cw = Some(Position::new(pos.row, cw_col));
-                    cell.ccw = Some(Position::new(pos.row, ccw_col));
+                    if col < width - 1 {
+                        new.east = Some(Position3D::new(level, row, col + 1));
+                    }
 
-                    cell.inward = Some(parent_pos.clone());
-                }
+                    if level > 0 {
+                        new.down = Some(Position3D::new(level - 1, row, col));
+                    }
 
-                if let Some(ref mut cell) = self.get_mut(&parent_pos) {
-                    cell.outward.push(pos);
+                    if level < levels - 1 {
+                        new.up = Some(Position3D::new(level + 1, row, col));
+                    }
+
+                    grid.cells.push(new);
                 }
             }
         }
+
+        grid
     }
 }
 
-impl MazeGrid for PolarGrid {
-    type CellType = PolarCell;
+impl MazeGrid for Grid3D {
+    type CellType = Cell3D;
 
-    fn cells(&self) -> &Vec<PolarCell> {
+    fn cells(&self) -> &Vec<Cell3D> {
         &self.cells
     }
 
-    fn get(&self, pos: &Position) -> Option<&PolarCell> {
+    fn get(&self, pos: &Position3D) -> Option<&Cell3D> {
         if !self.contains(pos) {
             return None;
         }
-        let idx = pos.col + self.row_offsets[pos.row];
+        let idx = pos.col + pos.row * self.width + pos.level * self.width * self.height;
         self.cells.get(idx)
     }
 
-    fn get_mut(&mut self, pos: &Position) -> Option<&mut PolarCell> {
+    fn get_mut(&mut self, pos: &Position3D) -> Option<&mut Cell3D> {
         if !self.contains(pos) {
             return None;
         }
-        let idx = pos.col + self.row_offsets[pos.row];
+        let idx = pos.col + pos.row * self.width + pos.level * self.width * self.height;
         self.cells.get_mut(idx)
     }
 
-    fn contains(&self, pos: &Position) -> bool {
+    fn contains(&self, pos: &Position3D) -> bool {
         // we don't have to check for negative numbers, since usize
-        pos.row < self.rows && pos.col < self.column_counts[pos.row]
+        pos.level < self.levels && pos.row < self.height && pos.col < self.width
     }
 
     fn to_string(&self, _: bool) -> String {
-        "to_string is meaningless for polar grids".to_owned()
+        "to_string is meaningless for 3d grids".to_owned()
     }
 }
 
 #[cfg(test)]
 mod test_grid {
     use super::*;
+    use algorithms::{braid, recursive_backtracker};
+    use rand::{SeedableRng, StdRng};
+    use solver::{dijkstra, solve};
 
     #[test]
     fn new() {
@@ -579,17 +1356,17 @@ mod test_grid {
         assert_eq!(grid.to_string(false), expected);
 
         {
-            let ref mut p = grid.get_mut(&Position::new(1, 1)).unwrap();
+            let p = &mut grid.get_mut(&Position::new(1, 1)).unwrap();
             p.update_weight(2);
         }
 
         {
-            let ref mut p = grid.get_mut(&Position::new(0, 1)).unwrap();
+            let p = &mut grid.get_mut(&Position::new(0, 1)).unwrap();
             p.update_weight(13);
         }
 
         {
-            let ref mut p = grid.get_mut(&Position::new(2, 0)).unwrap();
+            let p = &mut grid.get_mut(&Position::new(2, 0)).unwrap();
             p.update_weight(456);
         }
 
@@ -630,6 +1407,188 @@ mod test_grid {
         assert_eq!(grid.to_string(false), expected);
     }
 
+    #[test]
+    fn to_string_without_columns() {
+        assert_eq!(Grid::new(0, 2).to_string(false), "+\n|\n+\n|\n+\n");
+    }
+
+    #[test]
+    fn cylinder() {
+        let grid = Grid::with_topology(3, 2, Topology::Cylinder);
+
+        assert_eq!(grid.topology, Topology::Cylinder);
+
+        let cell = grid.get(&Position::new(0, 0)).unwrap();
+        assert_eq!(cell.west, Some(Position::new(0, 2)));
+        assert_eq!(cell.north, None);
+
+        let cell = grid.get(&Position::new(1, 2)).unwrap();
+        assert_eq!(cell.east, Some(Position::new(1, 0)));
+        assert_eq!(cell.south, None);
+    }
+
+    #[test]
+    fn torus() {
+        let grid = Grid::with_topology(3, 3, Topology::Torus);
+
+        for cell in grid.cells() {
+            assert_eq!(cell.neighbors().len(), 4);
+        }
+
+        let cell = grid.get(&Position::new(0, 1)).unwrap();
+        assert_eq!(cell.north, Some(Position::new(2, 1)));
+
+        let cell = grid.get(&Position::new(2, 1)).unwrap();
+        assert_eq!(cell.south, Some(Position::new(0, 1)));
+    }
+
+    #[test]
+    fn short_axes_do_not_wrap() {
+        let grid = Grid::with_topology(2, 2, Topology::Torus);
+
+        for cell in grid.cells() {
+            assert_eq!(cell.neighbors().len(), 2);
+        }
+    }
+
+    #[test]
+    fn to_string_wrapped() {
+        let mut grid = Grid::with_topology(3, 3, Topology::Torus);
+
+        grid.link(&Position::new(0, 2), &Position::new(0, 0));
+        grid.link(&Position::new(2, 1), &Position::new(0, 1));
+
+        // the seam passages leave trailing whitespace, so spell out each line
+        let expected = [
+            "+---+   +---+",
+            "    |   |    ",
+            "+---+---+---+",
+            "|   |   |   |",
+            "+---+---+---+",
+            "|   |   |   |",
+            "+---+   +---+",
+            "",
+        ].join("\n");
+
+        assert_eq!(grid.to_string(false), expected);
+    }
+
+    fn links(grid: &Grid) -> Vec<BTreeSet<Position>> {
+        grid.cells.iter().map(|c| c.links().clone()).collect()
+    }
+
+    #[test]
+    fn resetting() {
+        let mut grid = Grid::with_topology(5, 4, Topology::Torus);
+        recursive_backtracker(&mut grid, &mut StdRng::from_seed([4; 32]));
+        solve(&mut grid, &Position::new(0, 0), &Position::new(3, 4));
+
+        grid.clear_solution();
+        assert!(grid.cells.iter().all(|c| !c.in_solution()));
+        assert!(grid.cells.iter().any(|c| c.weight() > 0));
+
+        grid.clear_weights();
+        assert!(grid.cells.iter().all(|c| c.weight() == 0));
+
+        grid.reset_links();
+        assert_eq!(grid.to_string(false), Grid::new(5, 4).to_string(false));
+        assert_eq!(grid.neighbors(&Position::new(0, 0)).len(), 4);
+
+        // and it can be carved again
+        recursive_backtracker(&mut grid, &mut StdRng::from_seed([5; 32]));
+        assert_eq!(links(&grid).iter().map(|l| l.len()).sum::<usize>(), 2 * 19);
+    }
+
+    #[test]
+    fn from_str_round_trip() {
+        let mut rng = StdRng::from_seed([5; 32]);
+        for &topology in &[Topology::Plane, Topology::Cylinder, Topology::Torus] {
+            let mut grid = Grid::with_topology(7, 5, topology);
+            recursive_backtracker(&mut grid, &mut rng);
+            braid(&mut grid, 1.0, &mut rng);
+            dijkstra(&mut grid, &Position::new(2, 3));
+
+            let parsed = Grid::from_str(&grid.to_string(false)).unwrap();
+            assert_eq!(links(&parsed), links(&grid));
+            assert_eq!(parsed.to_string(false), grid.to_string(false));
+
+            let labelled = grid.to_string(true);
+            let parsed = labelled.parse::<Grid>().unwrap();
+            assert_eq!(links(&parsed), links(&grid));
+            assert_eq!(parsed.to_string(true), labelled);
+        }
+    }
+
+    #[test]
+    fn from_str_wrapped() {
+        let mut grid = Grid::with_topology(3, 3, Topology::Torus);
+        grid.link(&Position::new(0, 2), &Position::new(0, 0));
+
+        // only the east to west seam is open, which is all a cylinder needs
+        let parsed = Grid::from_str(&grid.to_string(false)).unwrap();
+        assert_eq!(parsed.topology, Topology::Cylinder);
+        assert_eq!(links(&parsed), links(&grid));
+
+        grid.link(&Position::new(2, 1), &Position::new(0, 1));
+        let parsed = Grid::from_str(&grid.to_string(false)).unwrap();
+        assert_eq!(parsed.topology, Topology::Torus);
+        assert_eq!(links(&parsed), links(&grid));
+    }
+
+    #[test]
+    fn from_str_hand_drawn() {
+        let maze = "\
++---+---+---+
+| S         |
++---+---+   +
+| E         |
++---+---+---+";
+
+        let grid = Grid::from_str(maze).unwrap();
+        assert_eq!((grid.width, grid.height), (3, 2));
+        assert_eq!(grid.topology, Topology::Plane);
+        assert!(grid.get(&Position::new(0, 2)).unwrap().is_linked_pos(&Position::new(1, 2)));
+        assert_eq!(grid.cells.iter().map(|c| c.links().len()).sum::<usize>(), 10);
+    }
+
+    #[test]
+    fn from_str_errors() {
+        let error = |maze: &[&str]| Grid::from_str(&maze.join("\n")).unwrap_err();
+
+        assert_eq!(error(&[]).to_string(), "line 1, column 1: the maze is empty");
+
+        let e = error(&["+---+--+", "|   |  |", "+---+--+"]);
+        assert_eq!((e.line, e.column), (1, 9));
+
+        let e = error(&["+---+", "|   |"]);
+        assert_eq!((e.line, e.column), (3, 1));
+
+        let e = error(&["+---+---+", "|   |  |", "+---+---+"]);
+        assert_eq!((e.line, e.column), (2, 9));
+        assert_eq!(e.message, "expected 9 characters but found 8");
+
+        let e = error(&["+---+---+", "|   |   |", "+---+---*"]);
+        assert_eq!((e.line, e.column), (3, 9));
+        assert_eq!(e.message, "expected a +");
+
+        let e = error(&["+---+---+", "|   #   |", "+---+---+"]);
+        assert_eq!((e.line, e.column), (2, 5));
+
+        let e = error(&["+---+- -+", "|   |   |", "+---+---+"]);
+        assert_eq!((e.line, e.column), (1, 6));
+        assert_eq!(e.message, "expected --- or spaces");
+
+        let e = error(&["+---+   +---+", "|   |   |   |", "+---+---+---+"]);
+        assert_eq!((e.line, e.column), (3, 6));
+        assert_eq!(e.message, "the bottom border doesn't match the top one");
+
+        let e = error(&["+---+---+", "    |   |", "+---+---+"]);
+        assert_eq!((e.line, e.column), (2, 9));
+
+        let e = error(&["+---+---+", "         ", "+---+---+"]);
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.message, "passages can only wrap around grids more than two columns wide");
+    }
 }
 
 #[cfg(test)]
@@ -790,3 +1749,352 @@ mod test_polar_grid {
     }
 
 }
+
+#[cfg(test)]
+mod test_hex_grid {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let width = 4;
+        let height = 3;
+        let grid = HexGrid::new(width, height);
+
+        assert_eq!(grid.width, width);
+        assert_eq!(grid.height, height);
+        assert_eq!(grid.cells().len(), width * height);
+
+        // even column, top row
+        let cell = grid.get(&Position::new(0, 0)).unwrap();
+        assert_eq!(cell.north, None);
+        assert_eq!(cell.south, Some(Position::new(1, 0)));
+        assert_eq!(cell.northeast, None);
+        assert_eq!(cell.southeast, Some(Position::new(0, 1)));
+        assert_eq!(cell.northwest, None);
+        assert_eq!(cell.southwest, None);
+
+        // odd column, middle row
+        let cell = grid.get(&Position::new(1, 1)).unwrap();
+        assert_eq!(cell.north, Some(Position::new(0, 1)));
+        assert_eq!(cell.south, Some(Position::new(2, 1)));
+        assert_eq!(cell.northeast, Some(Position::new(1, 2)));
+        assert_eq!(cell.southeast, Some(Position::new(2, 2)));
+        assert_eq!(cell.northwest, Some(Position::new(1, 0)));
+        assert_eq!(cell.southwest, Some(Position::new(2, 0)));
+
+        // odd column, bottom row
+        let cell = grid.get(&Position::new(2, 3)).unwrap();
+        assert_eq!(cell.south, None);
+        assert_eq!(cell.southwest, None);
+        assert_eq!(cell.northwest, Some(Position::new(2, 2)));
+        assert_eq!(cell.northeast, None);
+    }
+
+    #[test]
+    fn neighbors_are_symmetric() {
+        let grid = HexGrid::new(5, 4);
+
+        for cell in grid.cells() {
+            for neighbor in cell.neighbors() {
+                assert!(grid.neighbors(&neighbor).contains(cell.pos()));
+            }
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let width = 2;
+        let height = 3;
+        let grid = HexGrid::new(width, height);
+
+        assert!(!grid.contains(&Position::new(height, width)));
+        assert!(!grid.contains(&Position::new(height - 1, width)));
+        assert!(!grid.contains(&Position::new(height, width - 1)));
+        assert!(grid.contains(&Position::new(height - 1, width - 1)));
+        assert!(grid.contains(&Position::new(0, 0)));
+    }
+
+    #[test]
+    fn linking() {
+        let mut grid = HexGrid::new(3, 3);
+
+        let a = Position::new(1, 1);
+        let b = Position::new(2, 2);
+
+        grid.link(&a, &b);
+        assert!(grid.get(&a).unwrap().is_linked_pos(&b));
+        assert!(grid.get(&b).unwrap().is_linked_pos(&a));
+
+        grid.unlink(&a, &b);
+        assert!(!grid.get(&a).unwrap().is_linked_pos(&b));
+        assert!(!grid.get(&b).unwrap().is_linked_pos(&a));
+    }
+}
+
+#[cfg(test)]
+mod test_triangle_grid {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let width = 4;
+        let height = 3;
+        let grid = TriangleGrid::new(width, height);
+
+        assert_eq!(grid.width, width);
+        assert_eq!(grid.height, height);
+        assert_eq!(grid.cells().len(), width * height);
+
+        // upright, so no north neighbor
+        let cell = grid.get(&Position::new(0, 0)).unwrap();
+        assert_eq!(cell.north, None);
+        assert_eq!(cell.south, Some(Position::new(1, 0)));
+        assert_eq!(cell.east, Some(Position::new(0, 1)));
+        assert_eq!(cell.west, None);
+
+        // inverted, so no south neighbor
+        let cell = grid.get(&Position::new(1, 2)).unwrap();
+        assert_eq!(cell.north, Some(Position::new(0, 2)));
+        assert_eq!(cell.south, None);
+        assert_eq!(cell.east, Some(Position::new(1, 3)));
+        assert_eq!(cell.west, Some(Position::new(1, 1)));
+
+        // upright on the bottom row
+        let cell = grid.get(&Position::new(2, 2)).unwrap();
+        assert_eq!(cell.north, None);
+        assert_eq!(cell.south, None);
+    }
+
+    #[test]
+    fn neighbors_are_symmetric() {
+        let grid = TriangleGrid::new(5, 4);
+
+        for cell in grid.cells() {
+            assert!(cell.neighbors().len() <= 3);
+            for neighbor in cell.neighbors() {
+                assert!(grid.neighbors(&neighbor).contains(cell.pos()));
+            }
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let width = 2;
+        let height = 3;
+        let grid = TriangleGrid::new(width, height);
+
+        assert!(!grid.contains(&Position::new(height, width)));
+        assert!(!grid.contains(&Position::new(height - 1, width)));
+        assert!(grid.contains(&Position::new(height - 1, width - 1)));
+        assert!(grid.contains(&Position::new(0, 0)));
+    }
+}
+
+#[cfg(test)]
+mod test_masked_grid {
+    use super::*;
+
+    fn _mask() -> Mask {
+        Mask::from_txt(
+            "\
+X..
+XX.
+...
+",
+        )
+    }
+
+    #[test]
+    fn new() {
+        let grid = MaskedGrid::new(_mask());
+
+        assert_eq!(grid.width, 3);
+        assert_eq!(grid.height, 3);
+        assert_eq!(grid.cells().len(), 6);
+
+        let cell = grid.get(&Position::new(0, 1)).unwrap();
+        assert_eq!(cell.west, None);
+        assert_eq!(cell.east, Some(Position::new(0, 2)));
+        assert_eq!(cell.south, None);
+
+        let cell = grid.get(&Position::new(2, 0)).unwrap();
+        assert_eq!(cell.north, None);
+        assert_eq!(cell.east, Some(Position::new(2, 1)));
+        assert_eq!(cell.west, None);
+    }
+
+    #[test]
+    fn disabled_cells() {
+        let mut grid = MaskedGrid::new(_mask());
+
+        assert!(!grid.contains(&Position::new(1, 1)));
+        assert!(grid.get(&Position::new(1, 1)).is_none());
+        assert!(grid.get_mut(&Position::new(0, 0)).is_none());
+        assert!(grid.neighbors(&Position::new(1, 1)).is_empty());
+
+        for _ in 0..20 {
+            let pos = grid.random_pos().unwrap();
+            assert!(grid.mask.get(&pos));
+        }
+    }
+
+    #[test]
+    fn to_string_base() {
+        let mut grid = MaskedGrid::new(_mask());
+
+        grid.link(&Position::new(0, 1), &Position::new(0, 2));
+        grid.link(&Position::new(0, 2), &Position::new(1, 2));
+        grid.link(&Position::new(1, 2), &Position::new(2, 2));
+        grid.link(&Position::new(2, 2), &Position::new(2, 1));
+
+        let expected = "\
++   +---+---+
+    |       |
++   +---+   +
+        |   |
++---+---+   +
+|   |       |
++---+---+---+
+"
+            .to_string();
+
+        assert_eq!(grid.to_string(false), expected);
+    }
+}
+
+#[cfg(test)]
+mod test_weave_grid {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let grid = WeaveGrid::new(3, 4);
+
+        assert_eq!(grid.grid.width, 3);
+        assert_eq!(grid.grid.height, 4);
+        assert_eq!(grid.cells().len(), 12);
+    }
+
+    #[test]
+    fn neighbors_without_passages() {
+        let grid = WeaveGrid::new(3, 3);
+        let pos = Position::new(0, 1);
+
+        assert_eq!(grid.neighbors(&pos), grid.get(&pos).unwrap().neighbors());
+    }
+
+    #[test]
+    fn neighbors_across_horizontal_passage() {
+        let mut grid = WeaveGrid::new(3, 3);
+
+        grid.link(&Position::new(1, 0), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(1, 2));
+
+        let neighbors = grid.neighbors(&Position::new(0, 1));
+        assert!(neighbors.contains(&Position::new(2, 1)));
+
+        let neighbors = grid.neighbors(&Position::new(2, 1));
+        assert!(neighbors.contains(&Position::new(0, 1)));
+
+        // nothing to tunnel under horizontally
+        let neighbors = grid.neighbors(&Position::new(1, 0));
+        assert!(!neighbors.contains(&Position::new(1, 2)));
+    }
+
+    #[test]
+    fn no_tunnel_under_turns() {
+        let mut grid = WeaveGrid::new(3, 3);
+
+        grid.link(&Position::new(1, 0), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(0, 1));
+
+        let neighbors = grid.neighbors(&Position::new(2, 1));
+        assert!(!neighbors.contains(&Position::new(0, 1)));
+    }
+
+    #[test]
+    fn neighbors_across_vertical_passage() {
+        let mut grid = WeaveGrid::new(3, 3);
+
+        grid.link(&Position::new(0, 1), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(2, 1));
+
+        let neighbors = grid.neighbors(&Position::new(1, 0));
+        assert!(neighbors.contains(&Position::new(1, 2)));
+
+        // once the tunnel is dug the middle cell can't be entered from the
+        // sides, but the tunnel itself remains a neighbor
+        grid.link(&Position::new(1, 0), &Position::new(1, 2));
+        let neighbors = grid.neighbors(&Position::new(1, 0));
+        assert!(neighbors.contains(&Position::new(1, 2)));
+        assert!(!neighbors.contains(&Position::new(1, 1)));
+        assert!(!grid.neighbors(&Position::new(1, 1)).contains(&Position::new(1, 2)));
+        assert!(grid.get(&Position::new(1, 0))
+            .unwrap()
+            .is_linked_pos(&Position::new(1, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "through a tunnel")]
+    fn linking_into_a_tunnel() {
+        let mut grid = WeaveGrid::new(3, 3);
+
+        grid.link(&Position::new(0, 1), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(2, 1));
+        grid.link(&Position::new(1, 0), &Position::new(1, 2));
+
+        grid.link(&Position::new(1, 1), &Position::new(1, 0));
+    }
+}
+
+#[cfg(test)]
+mod test_grid_3d {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let grid = Grid3D::new(3, 4, 2);
+
+        assert_eq!(grid.levels, 3);
+        assert_eq!(grid.width, 4);
+        assert_eq!(grid.height, 2);
+        assert_eq!(grid.cells().len(), 24);
+
+        let cell = grid.get(&Position3D::new(0, 0, 0)).unwrap();
+        assert_eq!(cell.down, None);
+        assert_eq!(cell.up, Some(Position3D::new(1, 0, 0)));
+        assert_eq!(cell.north, None);
+        assert_eq!(cell.south, Some(Position3D::new(0, 1, 0)));
+
+        let cell = grid.get(&Position3D::new(2, 1, 3)).unwrap();
+        assert_eq!(cell.up, None);
+        assert_eq!(cell.down, Some(Position3D::new(1, 1, 3)));
+        assert_eq!(cell.east, None);
+        assert_eq!(cell.west, Some(Position3D::new(2, 1, 2)));
+    }
+
+    #[test]
+    fn getting() {
+        let grid = Grid3D::new(3, 4, 2);
+
+        for cell in grid.cells() {
+            assert_eq!(grid.get(cell.pos()).unwrap(), cell);
+        }
+
+        assert!(grid.get(&Position3D::new(3, 0, 0)).is_none());
+        assert!(grid.get(&Position3D::new(0, 2, 0)).is_none());
+        assert!(grid.get(&Position3D::new(0, 0, 4)).is_none());
+    }
+
+    #[test]
+    fn linking() {
+        let mut grid = Grid3D::new(2, 2, 2);
+
+        let a = Position3D::new(0, 1, 1);
+        let b = Position3D::new(1, 1, 1);
+
+        grid.link(&a, &b);
+        assert!(grid.get(&a).unwrap().is_linked_pos(&b));
+        assert!(grid.get(&b).unwrap().is_linked_pos(&a));
+    }
+}
@@ -0,0 +1,174 @@
+use data::pos::Position;
+use image;
+use image::ImageResult;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mask {
+    pub width: usize,
+    pub height: usize,
+    bits: Vec<bool>,
+}
+
+impl Mask {
+    pub fn new(width: usize, height: usize) -> Self {
+        Mask {
+            width,
+            height,
+            bits: vec![true; width * height],
+        }
+    }
+
+    // 'X' marks a disabled cell and anything else, spaces included, is
+    // enabled. Short lines are padded with disabled cells to the width of the
+    // longest line, so blank lines are rows of disabled cells
+    pub fn from_txt(template: &str) -> Self {
+        let lines = template.lines().collect::<Vec<&str>>();
+
+        let height = lines.len();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+        let mut mask = Mask::new(width, height);
+
+        for (row, line) in lines.iter().enumerate() {
+            let chars = line.chars().collect::<Vec<char>>();
+            for col in 0..width {
+                let enabled = match chars.get(col) {
+                    Some(c) => *c != 'X',
+                    None => false,
+                };
+                mask.set(&Position::new(row, col), enabled);
+            }
+        }
+
+        mask
+    }
+
+    // dark pixels are disabled and light pixels are enabled, one cell per pixel
+    pub fn from_png(path: &str) -> ImageResult<Self> {
+        let img = image::open(path)?.to_luma();
+        let (width, height) = img.dimensions();
+
+        let mut mask = Mask::new(width as usize, height as usize);
+
+        for (x, y, pixel) in img.enumerate_pixels() {
+            mask.set(&Position::new(y as usize, x as usize), pixel[0] >= 128);
+        }
+
+        Ok(mask)
+    }
+
+    pub fn contains(&self, pos: &Position) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    pub fn get(&self, pos: &Position) -> bool {
+        if !self.contains(pos) {
+            return false;
+        }
+        self.bits[pos.col + pos.row * self.width]
+    }
+
+    pub fn set(&mut self, pos: &Position, enabled: bool) {
+        if self.contains(pos) {
+            self.bits[pos.col + pos.row * self.width] = enabled;
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.iter().filter(|b| **b).count()
+    }
+}
+
+#[cfg(test)]
+mod test_mask {
+    use super::*;
+    use image::{GrayImage, Luma};
+    use std::{env, fs, process};
+
+    #[test]
+    fn new() {
+        let mask = Mask::new(3, 2);
+
+        assert_eq!(mask.width, 3);
+        assert_eq!(mask.height, 2);
+        assert_eq!(mask.count(), 6);
+        assert!(mask.get(&Position::new(1, 2)));
+        assert!(!mask.get(&Position::new(2, 2)));
+    }
+
+    #[test]
+    fn setting() {
+        let mut mask = Mask::new(3, 2);
+
+        mask.set(&Position::new(0, 1), false);
+        assert!(!mask.get(&Position::new(0, 1)));
+        assert_eq!(mask.count(), 5);
+
+        mask.set(&Position::new(0, 1), true);
+        assert!(mask.get(&Position::new(0, 1)));
+        assert_eq!(mask.count(), 6);
+
+        // out of bounds is ignored
+        mask.set(&Position::new(10, 10), false);
+        assert_eq!(mask.count(), 6);
+    }
+
+    #[test]
+    fn from_txt() {
+        let mask = Mask::from_txt(
+            "\
+X..X
+....
+.X
+",
+        );
+
+        assert_eq!(mask.width, 4);
+        assert_eq!(mask.height, 3);
+        assert_eq!(mask.count(), 7);
+
+        assert!(!mask.get(&Position::new(0, 0)));
+        assert!(mask.get(&Position::new(0, 1)));
+        assert!(!mask.get(&Position::new(0, 3)));
+        assert!(mask.get(&Position::new(2, 0)));
+        assert!(!mask.get(&Position::new(2, 1)));
+        assert!(!mask.get(&Position::new(2, 3)));
+    }
+
+    #[test]
+    fn from_txt_blank_lines() {
+        let mask = Mask::from_txt("..\n\n  \n.X. \n");
+
+        assert_eq!(mask.width, 4);
+        assert_eq!(mask.height, 4);
+        assert_eq!(mask.count(), 7);
+
+        assert!(!mask.get(&Position::new(0, 2)));
+        assert!(!mask.get(&Position::new(1, 0)));
+        assert!(mask.get(&Position::new(2, 1)));
+        assert!(!mask.get(&Position::new(2, 2)));
+        assert!(mask.get(&Position::new(3, 0)));
+        assert!(mask.get(&Position::new(3, 3)));
+        assert!(!mask.get(&Position::new(3, 1)));
+        assert!(mask.get(&Position::new(3, 2)));
+    }
+
+    #[test]
+    fn from_png() {
+        // named after the process so that parallel runs don't write over each other
+        let path = env::temp_dir().join(format!("meanderer_mask_{}.png", process::id()));
+        let name = path.to_str().unwrap();
+
+        // a dark pixel in the top right and a light one everywhere else
+        let img = GrayImage::from_fn(3, 2, |x, y| Luma([if (x, y) == (2, 0) { 0 } else { 255 }]));
+        img.save(name).unwrap();
+
+        let mask = Mask::from_png(name).unwrap();
+        assert_eq!(mask.width, 3);
+        assert_eq!(mask.height, 2);
+        assert_eq!(mask.count(), 5);
+        assert!(!mask.get(&Position::new(0, 2)));
+        assert!(mask.get(&Position::new(1, 2)));
+        fs::remove_file(name).unwrap();
+    }
+}
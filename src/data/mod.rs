@@ -1,7 +1,12 @@
-pub use self::cell::{Cell, MazeCell, PolarCell};
-pub use self::grid::{Grid, MazeGrid, PolarGrid};
-pub use self::pos::{MazePosition, Position};
+pub use self::cell::{Cell, Cell3D, HexCell, MazeCell, PolarCell, TriangleCell};
+pub use self::grid::{Grid, Grid3D, HexGrid, MaskedGrid, MazeGrid, ParseGridError, PolarGrid,
+                     Topology, TriangleGrid, WeaveGrid};
+pub use self::mask::Mask;
+pub use self::pos::{MazePosition, Position, Position3D};
 
 pub mod cell;
 pub mod grid;
+pub mod mask;
 pub mod pos;
+#[cfg(feature = "serialization")]
+pub mod schema;
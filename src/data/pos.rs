@@ -2,7 +2,10 @@ use std::hash::Hash;
 
 pub trait MazePosition: Clone + Eq + Hash + Ord + PartialEq + PartialOrd {}
 
+// positions are written as [row, col] pairs to keep saved grids small
 #[derive(Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialization", serde(from = "(usize, usize)", into = "(usize, usize)"))]
 pub struct Position {
     pub row: usize,
     pub col: usize,
@@ -10,8 +13,35 @@ pub struct Position {
 
 impl Position {
     pub fn new(row: usize, col: usize) -> Self {
-        Position { row: row, col: col }
+        Position { row, col }
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((row, col): (usize, usize)) -> Self {
+        Position::new(row, col)
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    fn from(pos: Position) -> Self {
+        (pos.row, pos.col)
     }
 }
 
 impl MazePosition for Position {}
+
+#[derive(Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Position3D {
+    pub level: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Position3D {
+    pub fn new(level: usize, row: usize, col: usize) -> Self {
+        Position3D { level, row, col }
+    }
+}
+
+impl MazePosition for Position3D {}
@@ -0,0 +1,411 @@
+use data::cell::MazeCell;
+use data::grid::{Grid, MazeGrid, PolarGrid, Topology};
+use data::pos::Position;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_json;
+use std::fs::File;
+use std::io::{Read, Write};
+
+// Grids are saved as the shape of the grid plus the passages carved into it,
+// since the neighbors of every cell follow from the shape. Positions are
+// [row, col] pairs and each link is listed once, with the lower position
+// first, and opens a passage in both directions:
+//
+//     {"kind": "grid", "width": 3, "height": 2, "topology": "plane",
+//      "links": [[[0, 0], [0, 1]], [[0, 1], [1, 1]], ...],
+//      "weights": [0, 1, 2, 5, 4, 3],
+//      "costs": [1, 1, 1, 1, 9, 1],
+//      "solution": [[0, 0], [0, 1], [1, 1]]}
+//
+//     {"kind": "polar", "rows": 4, "links": [...]}
+//
+// topology is one of plane, cylinder or torus and defaults to plane. weights
+// and costs hold the distance and traversal cost of every cell in row-major
+// order and solution lists the cells on the solved path. Each of them is left
+// out when there is nothing to save
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Schema {
+    Grid {
+        width: usize,
+        height: usize,
+        #[serde(default = "_plane")]
+        topology: Topology,
+        links: Vec<(Position, Position)>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        weights: Vec<u32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        costs: Vec<u32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        solution: Vec<Position>,
+    },
+    Polar {
+        rows: usize,
+        links: Vec<(Position, Position)>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        weights: Vec<u32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        costs: Vec<u32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        solution: Vec<Position>,
+    },
+}
+
+// anything bigger is refused before the cells are allocated. A polar grid has
+// about pi times its rows squared in cells, which keeps it under the same limit
+const MAX_CELLS: usize = 1 << 24;
+const MAX_POLAR_ROWS: usize = 2048;
+
+fn _plane() -> Topology {
+    Topology::Plane
+}
+
+fn _fmt(pos: &Position) -> String {
+    format!("[{}, {}]", pos.row, pos.col)
+}
+
+fn _links<G>(grid: &G) -> Vec<(Position, Position)>
+where
+    G: MazeGrid,
+    G::CellType: MazeCell<PositionType = Position>,
+{
+    let mut links = Vec::new();
+    for cell in grid.cells() {
+        for other in cell.links().iter().filter(|l| *l > cell.pos()) {
+            links.push((cell.pos().clone(), other.clone()));
+        }
+    }
+    links
+}
+
+fn _weights<G: MazeGrid>(grid: &G) -> Vec<u32> {
+    if grid.cells().iter().all(|c| c.weight() == 0) {
+        return Vec::new();
+    }
+    grid.cells().iter().map(|c| c.weight()).collect()
+}
+
+fn _costs<G: MazeGrid>(grid: &G) -> Vec<u32> {
+    if grid.cells().iter().all(|c| c.cost() == 1) {
+        return Vec::new();
+    }
+    grid.cells().iter().map(|c| c.cost()).collect()
+}
+
+fn _solution<G>(grid: &G) -> Vec<Position>
+where
+    G: MazeGrid,
+    G::CellType: MazeCell<PositionType = Position>,
+{
+    grid.cells()
+        .iter()
+        .filter(|c| c.in_solution())
+        .map(|c| c.pos().clone())
+        .collect()
+}
+
+// carves the saved links into a fresh grid, refusing anything that a
+// generator could not have produced
+fn _restore<G>(
+    mut grid: G,
+    links: &[(Position, Position)],
+    (weights, costs): (&[u32], &[u32]),
+    solution: &[Position],
+) -> Result<G, String>
+where
+    G: MazeGrid,
+    G::CellType: MazeCell<PositionType = Position>,
+{
+    for (i, (a, b)) in links.iter().enumerate() {
+        for pos in &[a, b] {
+            if !grid.contains(pos) {
+                return Err(format!("link {}: {} is outside the grid", i, _fmt(pos)));
+            }
+        }
+
+        if !grid.neighbors(a).contains(b) || !grid.neighbors(b).contains(a) {
+            return Err(format!(
+                "link {}: {} and {} are not neighbors",
+                i,
+                _fmt(a),
+                _fmt(b)
+            ));
+        }
+
+        if grid.get(a).unwrap().is_linked_pos(b) || grid.get(b).unwrap().is_linked_pos(a) {
+            return Err(format!(
+                "link {}: {} and {} are linked more than once",
+                i,
+                _fmt(a),
+                _fmt(b)
+            ));
+        }
+
+        grid.link(a, b);
+    }
+
+    let positions = grid.positions();
+    for &(name, values) in &[("weights", weights), ("costs", costs)] {
+        if !values.is_empty() && values.len() != positions.len() {
+            return Err(format!(
+                "expected {} {} but found {}",
+                positions.len(),
+                name,
+                values.len()
+            ));
+        }
+    }
+
+    for (pos, weight) in positions.iter().zip(weights) {
+        grid.get_mut(pos).unwrap().update_weight(*weight);
+    }
+    for (pos, cost) in positions.iter().zip(costs) {
+        grid.get_mut(pos).unwrap().update_cost(*cost);
+    }
+
+    for pos in solution {
+        match grid.get_mut(pos) {
+            Some(cell) => cell.mark_in_solution(),
+            None => return Err(format!("solution cell {} is outside the grid", _fmt(pos))),
+        }
+    }
+
+    Ok(grid)
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Schema::Grid {
+            width: self.width,
+            height: self.height,
+            topology: self.topology,
+            links: _links(self),
+            weights: _weights(self),
+            costs: _costs(self),
+            solution: _solution(self),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Schema::deserialize(deserializer)? {
+            Schema::Grid {
+                width,
+                height,
+                topology,
+                links,
+                weights,
+                costs,
+                solution,
+            } => {
+                if width == 0 || height == 0 {
+                    return Err(de::Error::custom(format!(
+                        "invalid grid size {}x{}",
+                        width, height
+                    )));
+                }
+                if width.checked_mul(height).map_or(true, |cells| cells > MAX_CELLS) {
+                    return Err(de::Error::custom(format!(
+                        "grid size {}x{} is over the limit of {} cells",
+                        width, height, MAX_CELLS
+                    )));
+                }
+                let grid = Grid::with_topology(width, height, topology);
+                _restore(grid, &links, (&weights, &costs), &solution)
+                    .map_err(de::Error::custom)
+            }
+            Schema::Polar { .. } => {
+                Err(de::Error::custom("expected a grid but found a polar grid"))
+            }
+        }
+    }
+}
+
+impl Serialize for PolarGrid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Schema::Polar {
+            rows: self.rows,
+            links: _links(self),
+            weights: _weights(self),
+            costs: _costs(self),
+            solution: _solution(self),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PolarGrid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Schema::deserialize(deserializer)? {
+            Schema::Polar {
+                rows,
+                links,
+                weights,
+                costs,
+                solution,
+            } => {
+                if rows == 0 {
+                    return Err(de::Error::custom("a polar grid needs at least one row"));
+                }
+                if rows > MAX_POLAR_ROWS {
+                    return Err(de::Error::custom(format!(
+                        "{} rows is over the limit of {} for polar grids",
+                        rows, MAX_POLAR_ROWS
+                    )));
+                }
+                let grid = PolarGrid::new(rows);
+                _restore(grid, &links, (&weights, &costs), &solution)
+                    .map_err(de::Error::custom)
+            }
+            Schema::Grid { .. } => {
+                Err(de::Error::custom("expected a polar grid but found a grid"))
+            }
+        }
+    }
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+pub fn from_json<'de, T: Deserialize<'de>>(json: &'de str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+pub fn save<T: Serialize>(value: &T, name: &str) -> Result<(), String> {
+    File::create(name)
+        .and_then(|mut f| f.write_all(to_json(value).as_bytes()))
+        .map_err(|e| format!("couldn't write {}: {}", name, e))
+}
+
+pub fn load<T>(name: &str) -> Result<T, String>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let mut json = String::new();
+    File::open(name)
+        .and_then(|mut f| f.read_to_string(&mut json))
+        .map_err(|e| format!("couldn't read {}: {}", name, e))?;
+    from_json(&json)
+}
+
+#[cfg(test)]
+mod test_schema {
+    use super::*;
+    use algorithms::{braid, recursive_backtracker};
+    use data::cell::Cell;
+    use rand::{SeedableRng, StdRng};
+    use solver::solve;
+
+    fn links<G>(grid: &G) -> Vec<Vec<Position>>
+    where
+        G: MazeGrid,
+        G::CellType: MazeCell<PositionType = Position>,
+    {
+        grid.cells()
+            .iter()
+            .map(|c| c.links().iter().cloned().collect())
+            .collect()
+    }
+
+    #[test]
+    fn positions_and_cells() {
+        assert_eq!(to_json(&Position::new(2, 3)), "[2,3]");
+        assert_eq!(from_json::<Position>("[4, 5]"), Ok(Position::new(4, 5)));
+
+        let mut cell = Cell::new(0, 0);
+        cell.east = Some(Position::new(0, 1));
+        cell.link(&Position::new(0, 1));
+        let loaded: Cell = from_json(&to_json(&cell)).unwrap();
+        assert_eq!(loaded.east, cell.east);
+        assert!(loaded.is_linked_pos(&Position::new(0, 1)));
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let mut rng = StdRng::from_seed([2; 32]);
+        let mut grid = Grid::with_topology(6, 4, Topology::Cylinder);
+        recursive_backtracker(&mut grid, &mut rng);
+        braid(&mut grid, 0.5, &mut rng);
+        grid.get_mut(&Position::new(1, 2)).unwrap().update_cost(7);
+        solve(&mut grid, &Position::new(0, 0), &Position::new(3, 5));
+
+        let json = to_json(&grid);
+        assert!(json.starts_with("{\"kind\":\"grid\",\"width\":6,\"height\":4"));
+        assert!(json.contains("\"topology\":\"cylinder\""));
+
+        let loaded: Grid = from_json(&json).unwrap();
+        assert_eq!(loaded.topology, Topology::Cylinder);
+        assert_eq!(loaded.get(&Position::new(1, 2)).unwrap().cost(), 7);
+        assert_eq!(links(&loaded), links(&grid));
+        assert_eq!(loaded.to_string(true), grid.to_string(true));
+        assert_eq!(_solution(&loaded), _solution(&grid));
+    }
+
+    #[test]
+    fn polar_round_trip() {
+        let mut grid = PolarGrid::new(5);
+        recursive_backtracker(&mut grid, &mut StdRng::from_seed([3; 32]));
+
+        let json = to_json(&grid);
+        assert!(!json.contains("weights"));
+        assert!(!json.contains("solution"));
+
+        let loaded: PolarGrid = from_json(&json).unwrap();
+        assert_eq!(links(&loaded), links(&grid));
+    }
+
+    #[test]
+    fn minimal_grid() {
+        let json = r#"{"kind": "grid", "width": 2, "height": 1, "links": [[[0, 0], [0, 1]]]}"#;
+        let grid: Grid = from_json(json).unwrap();
+        assert_eq!(grid.topology, Topology::Plane);
+        assert!(grid.get(&Position::new(0, 1)).unwrap().is_linked_pos(&Position::new(0, 0)));
+    }
+
+    #[test]
+    fn invalid_grids() {
+        let errors = [
+            (r#"{"kind": "polar", "rows": 2, "links": []}"#, "expected a grid"),
+            (r#"{"kind": "grid", "width": 0, "height": 2, "links": []}"#, "invalid grid size"),
+            (
+                r#"{"kind": "grid", "width": 100000, "height": 100000, "links": []}"#,
+                "over the limit",
+            ),
+            (
+                r#"{"kind": "grid", "width": 2, "height": 2, "links": [[[0, 0], [1, 1]]]}"#,
+                "link 0: [0, 0] and [1, 1] are not neighbors",
+            ),
+            (
+                r#"{"kind": "grid", "width": 2, "height": 2, "links": [[[0, 0], [0, 2]]]}"#,
+                "link 0: [0, 2] is outside the grid",
+            ),
+            (
+                r#"{"kind": "grid", "width": 2, "height": 2,
+                    "links": [[[0, 0], [0, 1]], [[0, 1], [0, 0]]]}"#,
+                "link 1: [0, 1] and [0, 0] are linked more than once",
+            ),
+            (
+                r#"{"kind": "grid", "width": 2, "height": 2, "links": [], "weights": [1]}"#,
+                "expected 4 weights but found 1",
+            ),
+            (
+                r#"{"kind": "grid", "width": 2, "height": 2, "links": [], "solution": [[5, 5]]}"#,
+                "solution cell [5, 5] is outside the grid",
+            ),
+            (r#"{"kind": "hex", "width": 2}"#, "unknown variant"),
+        ];
+
+        for &(json, expected) in &errors {
+            let error = from_json::<Grid>(json).unwrap_err();
+            assert!(error.contains(expected), "{} does not mention {}", error, expected);
+        }
+
+        let error = from_json::<PolarGrid>(r#"{"kind": "polar", "rows": 0, "links": []}"#);
+        assert!(error.unwrap_err().contains("at least one row"));
+
+        let error = from_json::<PolarGrid>(r#"{"kind": "polar", "rows": 100000, "links": []}"#);
+        assert!(error.unwrap_err().contains("over the limit"));
+    }
+}
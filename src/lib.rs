@@ -4,15 +4,21 @@ extern crate imageproc;
 extern crate itertools;
 extern crate linked_hash_set;
 extern crate rand;
+#[cfg(feature = "serialization")]
+extern crate serde;
+#[cfg(feature = "serialization")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serialization")]
+extern crate serde_json;
 extern crate termion;
 extern crate tui;
 
 pub mod algorithms;
+pub mod analysis;
+pub mod animation;
 pub mod data;
 pub mod rendering;
 pub mod solver;
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-}
+pub mod svg;
+pub mod terminal;
@@ -1,6 +1,7 @@
-use data::cell::{Cell, MazeCell, PolarCell};
-use data::grid::{Grid, MazeGrid, PolarGrid};
-use data::pos::Position;
+use data::cell::{Cell, Cell3D, HexCell, MazeCell, PolarCell, TriangleCell};
+use data::grid::{Grid, Grid3D, HexGrid, MaskedGrid, MazeGrid, PolarGrid, Topology,
+                 TriangleGrid};
+use data::pos::{Position, Position3D};
 use image::{Rgb, RgbImage};
 use imageproc::drawing::{draw_antialiased_line_segment_mut, draw_convex_polygon_mut,
                          draw_filled_rect_mut, draw_hollow_circle_mut, Point};
@@ -12,6 +13,8 @@ use std::f32::consts::PI;
 const DEFAULT_CELL_SIZE: u32 = 30;
 const DEFAULT_WALL_THICKNESS: u32 = 5;
 
+// color_fn is only ever compared in tests against the fn it was built with
+#[allow(unpredictable_function_pointer_comparisons)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Style {
     pub cell_size: u32,
@@ -21,6 +24,7 @@ pub struct Style {
     pub color_fn: Option<fn(weight: u32, max_weight: u32) -> Rgb<u8>>,
     pub draw_solution: bool,
     pub solution_color: Rgb<u8>,
+    pub inset: u32,
 }
 
 pub struct StyleBuilder {
@@ -31,6 +35,13 @@ pub struct StyleBuilder {
     pub color_fn: Option<fn(weight: u32, max_weight: u32) -> Rgb<u8>>,
     pub draw_solution: bool,
     pub solution_color: Rgb<u8>,
+    pub inset: u32,
+}
+
+impl Default for StyleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StyleBuilder {
@@ -43,6 +54,7 @@ impl StyleBuilder {
             color_fn: None,
             draw_solution: false,
             solution_color: Rgb([200, 0, 0]),
+            inset: 0,
         }
     }
 
@@ -81,6 +93,13 @@ impl StyleBuilder {
         self
     }
 
+    // drawing passages inset from the cell borders leaves room to show
+    // passages tunneling under cells in weave mazes
+    pub fn inset(mut self, size: u32) -> Self {
+        self.inset = size;
+        self
+    }
+
     pub fn build(&self) -> Style {
         Style {
             cell_size: self.cell_size,
@@ -90,6 +109,7 @@ impl StyleBuilder {
             color_fn: self.color_fn,
             draw_solution: self.draw_solution,
             solution_color: self.solution_color,
+            inset: self.inset,
         }
     }
 }
@@ -102,6 +122,10 @@ pub fn default_color_fn(weight: u32, max_weight: u32) -> Rgb<u8> {
 }
 
 pub fn png(grid: &Grid, style: &Style, name: &str) {
+    grid_image(grid, style).save(name).unwrap()
+}
+
+pub fn grid_image(grid: &Grid, style: &Style) -> RgbImage {
     let width =
         grid.width as u32 * style.cell_size + (grid.width as u32 + 1) * style.wall_thickness;
     let height =
@@ -121,6 +145,11 @@ pub fn png(grid: &Grid, style: &Style, name: &str) {
         style.background_color,
     );
 
+    if style.inset > 0 {
+        _inset_cells(&mut img, grid, style, max_weight);
+        return img;
+    }
+
     // top
     draw_filled_rect_mut(
         &mut img,
@@ -181,12 +210,331 @@ pub fn png(grid: &Grid, style: &Style, name: &str) {
         style.wall_color,
     );
 
+    if grid.topology != Topology::Plane {
+        _open_seams(&mut img, grid, style, max_weight);
+    }
+
+    img
+}
+
+fn _seam_color(style: &Style, cell: &Cell, other: &Cell, max_weight: u32) -> Rgb<u8> {
+    if style.draw_solution && cell.in_solution() && other.in_solution() {
+        return style.solution_color;
+    }
+
+    match style.color_fn {
+        Some(f) => f(cell.weight(), max_weight),
+        None => style.background_color,
+    }
+}
+
+// passages that wrap around the grid are drawn as gaps in the border on both
+// sides of the seam
+fn _open_seams(img: &mut RgbImage, grid: &Grid, style: &Style, max_weight: u32) {
+    let t = style.wall_thickness;
+    let p = (style.cell_size + t) as i32;
+    let far_x = grid.width as i32 * p;
+    let far_y = grid.height as i32 * p;
+
+    for row in 0..grid.height {
+        let west = grid.get(&Position::new(row, 0)).unwrap();
+        let east = grid.get(&Position::new(row, grid.width - 1)).unwrap();
+
+        if east.east.is_some() && east.is_linked(west) {
+            let y = row as i32 * p + t as i32;
+            let west_color = _seam_color(style, west, east, max_weight);
+            let east_color = _seam_color(style, east, west, max_weight);
+
+            draw_filled_rect_mut(img, Rect::at(0, y).of_size(t, style.cell_size), west_color);
+            draw_filled_rect_mut(img, Rect::at(far_x, y).of_size(t, style.cell_size), east_color);
+        }
+    }
+
+    for col in 0..grid.width {
+        let north = grid.get(&Position::new(0, col)).unwrap();
+        let south = grid.get(&Position::new(grid.height - 1, col)).unwrap();
+
+        if south.south.is_some() && south.is_linked(north) {
+            let x = col as i32 * p + t as i32;
+            let north_color = _seam_color(style, north, south, max_weight);
+            let south_color = _seam_color(style, south, north, max_weight);
+
+            draw_filled_rect_mut(img, Rect::at(x, 0).of_size(style.cell_size, t), north_color);
+            draw_filled_rect_mut(img, Rect::at(x, far_y).of_size(style.cell_size, t), south_color);
+        }
+    }
+}
+
+fn _inset_color(style: &Style, cell: &Cell, max_weight: u32) -> Option<Rgb<u8>> {
+    if style.draw_solution && cell.in_solution() {
+        Some(style.solution_color)
+    } else {
+        style.color_fn.map(|f| f(cell.weight(), max_weight))
+    }
+}
+
+fn _tunnel_color(style: &Style, a: &Cell, b: &Cell, max_weight: u32) -> Option<Rgb<u8>> {
+    if style.draw_solution && a.in_solution() && b.in_solution() {
+        Some(style.solution_color)
+    } else {
+        style
+            .color_fn
+            .map(|f| f(a.weight().min(b.weight()), max_weight))
+    }
+}
+
+fn _inset_linked(
+    grid: &Grid,
+    cell: &Cell,
+    neighbor: &Option<Position>,
+    step: fn(&Cell) -> &Option<Position>,
+) -> bool {
+    match *neighbor {
+        Some(ref pos) => {
+            // a link to the cell two steps away is a tunnel starting here
+            cell.is_linked_pos(pos)
+                || match grid.get(pos).map(step) {
+                    Some(Some(beyond)) => cell.is_linked_pos(beyond),
+                    _ => false,
+                }
+        }
+        None => false,
+    }
+}
+
+fn _inset_cells(img: &mut RgbImage, grid: &Grid, style: &Style, max_weight: u32) {
+    let t = style.wall_thickness as i32;
+    let p = (style.cell_size + style.wall_thickness) as i32;
+    // leave at least a wall's width of room on each side for tunnels
+    let inset = (style.inset as i32).max(t).min((p - t) / 2);
+
+    let mut fills = Vec::new();
+    let mut walls = Vec::new();
+
+    for cell in &grid.cells {
+        let x1 = cell.pos.col as i32 * p;
+        let x2 = x1 + inset;
+        let x4 = x1 + p;
+        let x3 = x4 - inset;
+
+        let y1 = cell.pos.row as i32 * p;
+        let y2 = y1 + inset;
+        let y4 = y1 + p;
+        let y3 = y4 - inset;
+
+        let north = _inset_linked(grid, cell, &cell.north, |c| &c.north);
+        let south = _inset_linked(grid, cell, &cell.south, |c| &c.south);
+        let east = _inset_linked(grid, cell, &cell.east, |c| &c.east);
+        let west = _inset_linked(grid, cell, &cell.west, |c| &c.west);
+
+        if let Some(color) = _inset_color(style, cell, max_weight) {
+            fills.push((
+                Rect::at(x2, y2).of_size((x3 - x2 + t) as u32, (y3 - y2 + t) as u32),
+                color,
+            ));
+
+            if north {
+                fills.push((
+                    Rect::at(x2, y1).of_size((x3 - x2 + t) as u32, inset as u32),
+                    color,
+                ));
+            }
+
+            if south {
+                fills.push((
+                    Rect::at(x2, y3).of_size((x3 - x2 + t) as u32, inset as u32),
+                    color,
+                ));
+            }
+
+            if west {
+                fills.push((
+                    Rect::at(x1, y2).of_size(inset as u32, (y3 - y2 + t) as u32),
+                    color,
+                ));
+            }
+
+            if east {
+                fills.push((
+                    Rect::at(x3, y2).of_size(inset as u32, (y3 - y2 + t) as u32),
+                    color,
+                ));
+            }
+        }
+
+        if north {
+            walls.push(Rect::at(x2, y1).of_size(t as u32, (y2 - y1) as u32));
+            walls.push(Rect::at(x3, y1).of_size(t as u32, (y2 - y1) as u32));
+        } else {
+            walls.push(Rect::at(x2, y2).of_size((x3 - x2 + t) as u32, t as u32));
+        }
+
+        if south {
+            walls.push(Rect::at(x2, y3).of_size(t as u32, (y4 - y3) as u32));
+            walls.push(Rect::at(x3, y3).of_size(t as u32, (y4 - y3) as u32));
+        } else {
+            walls.push(Rect::at(x2, y3).of_size((x3 - x2 + t) as u32, t as u32));
+        }
+
+        if west {
+            walls.push(Rect::at(x1, y2).of_size((x2 - x1) as u32, t as u32));
+            walls.push(Rect::at(x1, y3).of_size((x2 - x1) as u32, t as u32));
+        } else {
+            walls.push(Rect::at(x2, y2).of_size(t as u32, (y3 - y2 + t) as u32));
+        }
+
+        if east {
+            walls.push(Rect::at(x3, y2).of_size((x4 - x3) as u32, t as u32));
+            walls.push(Rect::at(x3, y3).of_size((x4 - x3) as u32, t as u32));
+        } else {
+            walls.push(Rect::at(x3, y2).of_size(t as u32, (y3 - y2 + t) as u32));
+        }
+
+        // tunnels passing under this cell only show up in its margins
+        if let (Some(n), Some(s)) = (&cell.north, &cell.south) {
+            if let (Some(n), Some(s)) = (grid.get(n), grid.get(s)) {
+                if n.is_linked(s) {
+                    if let Some(color) = _tunnel_color(style, n, s, max_weight) {
+                        fills.push((
+                            Rect::at(x2, y1).of_size((x3 - x2 + t) as u32, inset as u32),
+                            color,
+                        ));
+                        fills.push((
+                            Rect::at(x2, y3).of_size((x3 - x2 + t) as u32, inset as u32),
+                            color,
+                        ));
+                    }
+
+                    walls.push(Rect::at(x2, y1).of_size(t as u32, (y2 - y1) as u32));
+                    walls.push(Rect::at(x3, y1).of_size(t as u32, (y2 - y1) as u32));
+                    walls.push(Rect::at(x2, y3).of_size(t as u32, (y4 - y3) as u32));
+                    walls.push(Rect::at(x3, y3).of_size(t as u32, (y4 - y3) as u32));
+                }
+            }
+        }
+
+        if let (Some(w), Some(e)) = (&cell.west, &cell.east) {
+            if let (Some(w), Some(e)) = (grid.get(w), grid.get(e)) {
+                if w.is_linked(e) {
+                    if let Some(color) = _tunnel_color(style, w, e, max_weight) {
+                        fills.push((
+                            Rect::at(x1, y2).of_size(inset as u32, (y3 - y2 + t) as u32),
+                            color,
+                        ));
+                        fills.push((
+                            Rect::at(x3, y2).of_size(inset as u32, (y3 - y2 + t) as u32),
+                            color,
+                        ));
+                    }
+
+                    walls.push(Rect::at(x1, y2).of_size((x2 - x1) as u32, t as u32));
+                    walls.push(Rect::at(x1, y3).of_size((x2 - x1) as u32, t as u32));
+                    walls.push(Rect::at(x3, y2).of_size((x4 - x3) as u32, t as u32));
+                    walls.push(Rect::at(x3, y3).of_size((x4 - x3) as u32, t as u32));
+                }
+            }
+        }
+    }
+
+    // everything is filled before any walls are drawn so that neighboring
+    // cells can't paint over each other's walls
+    for (rect, color) in fills {
+        draw_filled_rect_mut(img, rect, color);
+    }
+
+    for rect in walls {
+        draw_filled_rect_mut(img, rect, style.wall_color);
+    }
+}
+
+pub fn masked_png(grid: &MaskedGrid, style: &Style, name: &str) {
+    let width =
+        grid.width as u32 * style.cell_size + (grid.width as u32 + 1) * style.wall_thickness;
+    let height =
+        grid.height as u32 * style.cell_size + (grid.height as u32 + 1) * style.wall_thickness;
+    let max_weight = grid.cells
+        .iter()
+        .max_by_key(|c| c.weight())
+        .unwrap_or(&Cell::new(0, 0))
+        .weight();
+
+    let mut img = RgbImage::new(width, height);
+
+    // background
+    draw_filled_rect_mut(
+        &mut img,
+        Rect::at(0, 0).of_size(width, height),
+        style.background_color,
+    );
+
+    for cell in &grid.cells {
+        let x = (cell.pos.col as i32 * style.cell_size as i32)
+            + (cell.pos.col + 1) as i32 * style.wall_thickness as i32;
+        let y = (cell.pos.row as i32 * style.cell_size as i32)
+            + (cell.pos.row + 1) as i32 * style.wall_thickness as i32;
+        let w = style.cell_size + style.wall_thickness;
+        let h = style.cell_size + style.wall_thickness;
+
+        if style.draw_solution && cell.in_solution() {
+            draw_filled_rect_mut(&mut img, Rect::at(x, y).of_size(w, h), style.solution_color);
+        } else if let Some(f) = style.color_fn {
+            draw_filled_rect_mut(
+                &mut img,
+                Rect::at(x, y).of_size(w, h),
+                f(cell.weight(), max_weight),
+            );
+        }
+    }
+
+    // walls are drawn after every cell is filled since, unlike png, a cell
+    // can be bordered by a disabled position on any side
+    for cell in &grid.cells {
+        let x = cell.pos.col as i32 * (style.cell_size + style.wall_thickness) as i32;
+        let y = cell.pos.row as i32 * (style.cell_size + style.wall_thickness) as i32;
+        let span = style.cell_size + 2 * style.wall_thickness;
+        let step = (style.cell_size + style.wall_thickness) as i32;
+
+        if cell.north.is_none() {
+            draw_filled_rect_mut(
+                &mut img,
+                Rect::at(x, y).of_size(span, style.wall_thickness),
+                style.wall_color,
+            );
+        }
+
+        if cell.west.is_none() {
+            draw_filled_rect_mut(
+                &mut img,
+                Rect::at(x, y).of_size(style.wall_thickness, span),
+                style.wall_color,
+            );
+        }
+
+        match cell.east {
+            Some(ref east) => _east_wall(&mut img, grid, style, cell, east, max_weight),
+            None => draw_filled_rect_mut(
+                &mut img,
+                Rect::at(x + step, y).of_size(style.wall_thickness, span),
+                style.wall_color,
+            ),
+        }
+
+        match cell.south {
+            Some(ref south) => _south_wall(&mut img, grid, style, cell, south, max_weight),
+            None => draw_filled_rect_mut(
+                &mut img,
+                Rect::at(x, y + step).of_size(span, style.wall_thickness),
+                style.wall_color,
+            ),
+        }
+    }
+
     img.save(name).unwrap()
 }
 
-fn _east_wall(
+fn _east_wall<G: MazeGrid<CellType = Cell>>(
     img: &mut RgbImage,
-    grid: &Grid,
+    grid: &G,
     style: &Style,
     cell: &Cell,
     east: &Position,
@@ -200,7 +548,7 @@ fn _east_wall(
 
         draw_filled_rect_mut(img, Rect::at(x, y).of_size(w, h), style.wall_color);
     } else if style.draw_solution && cell.in_solution() {
-        if let Some(ref east_cell) = grid.get(east) {
+        if let Some(east_cell) = grid.get(east) {
             if !east_cell.in_solution() {
                 let x = (cell.pos.col + 1) as i32 * (style.cell_size + style.wall_thickness) as i32;
                 let y = cell.pos.row as i32 * (style.cell_size + style.wall_thickness) as i32
@@ -219,9 +567,9 @@ fn _east_wall(
     }
 }
 
-fn _south_wall(
+fn _south_wall<G: MazeGrid<CellType = Cell>>(
     img: &mut RgbImage,
-    grid: &Grid,
+    grid: &G,
     style: &Style,
     cell: &Cell,
     south: &Position,
@@ -235,7 +583,7 @@ fn _south_wall(
 
         draw_filled_rect_mut(img, Rect::at(x, y).of_size(w, h), style.wall_color);
     } else if style.draw_solution && cell.in_solution() {
-        if let Some(ref south_cell) = grid.get(south) {
+        if let Some(south_cell) = grid.get(south) {
             if !south_cell.in_solution() {
                 let x = cell.pos.col as i32 * (style.cell_size + style.wall_thickness) as i32
                     + style.wall_thickness as i32;
@@ -255,6 +603,10 @@ fn _south_wall(
 }
 
 pub fn polar_png(grid: &PolarGrid, style: &Style, name: &str) {
+    polar_image(grid, style).save(name).unwrap()
+}
+
+pub fn polar_image(grid: &PolarGrid, style: &Style) -> RgbImage {
     let offset = 5;
     let size = (grid.rows * 2) as u32 * style.cell_size + offset * 2;
     let center = (size as f32 / 2.0).round() as i32;
@@ -368,9 +720,355 @@ pub fn polar_png(grid: &PolarGrid, style: &Style, name: &str) {
         style.wall_color,
     );
 
+    img
+}
+
+pub fn hex_png(grid: &HexGrid, style: &Style, name: &str) {
+    let offset = 5.0;
+    let size = style.cell_size as f32 / 2.0;
+    // a is the horizontal distance from the center to the near corners, b is
+    // the vertical distance from the center to the flat top and bottom edges
+    let a_size = size / 2.0;
+    let b_size = size * 3.0_f32.sqrt() / 2.0;
+    let height = b_size * 2.0;
+
+    let img_width = (3.0 * a_size * grid.width as f32 + a_size + offset * 2.0).round() as u32;
+    let img_height = (height * grid.height as f32 + b_size + offset * 2.0).round() as u32;
+    let max_weight = grid.cells
+        .iter()
+        .max_by_key(|c| c.weight())
+        .unwrap_or(&HexCell::new(0, 0))
+        .weight();
+
+    let mut img = RgbImage::new(img_width, img_height);
+
+    let mut walls = Vec::new();
+
+    // background
+    draw_filled_rect_mut(
+        &mut img,
+        Rect::at(0, 0).of_size(img_width, img_height),
+        style.background_color,
+    );
+
+    for cell in &grid.cells {
+        let pos = cell.pos();
+        let cx = offset + size + 3.0 * pos.col as f32 * a_size;
+        let mut cy = offset + b_size + pos.row as f32 * height;
+
+        if pos.col % 2 == 1 {
+            cy += b_size;
+        }
+
+        // f/n = far/near, n/s/e/w = north/south/east/west
+        let x_fw = (cx - size) as i32;
+        let x_nw = (cx - a_size) as i32;
+        let x_ne = (cx + a_size) as i32;
+        let x_fe = (cx + size) as i32;
+
+        let y_n = (cy - b_size) as i32;
+        let y_m = cy as i32;
+        let y_s = (cy + b_size) as i32;
+
+        if style.draw_solution || style.color_fn.is_some() {
+            let bounds = vec![
+                Point::new(x_fw, y_m),
+                Point::new(x_nw, y_n),
+                Point::new(x_ne, y_n),
+                Point::new(x_fe, y_m),
+                Point::new(x_ne, y_s),
+                Point::new(x_nw, y_s),
+            ];
+
+            if style.draw_solution && cell.in_solution() {
+                draw_convex_polygon_mut(&mut img, bounds.as_slice(), style.solution_color);
+            } else {
+                let color = match style.color_fn {
+                    Some(f) => f(cell.weight(), max_weight),
+                    None => style.background_color,
+                };
+                draw_convex_polygon_mut(&mut img, bounds.as_slice(), color);
+            }
+        }
+
+        // the west and north walls are only drawn on the border, every other
+        // wall is drawn by the cell on the other side of it
+        if cell.southwest.is_none() {
+            walls.push(((x_fw, y_m), (x_nw, y_s)));
+        }
+
+        if cell.northwest.is_none() {
+            walls.push(((x_fw, y_m), (x_nw, y_n)));
+        }
+
+        if cell.north.is_none() {
+            walls.push(((x_nw, y_n), (x_ne, y_n)));
+        }
+
+        if !_hex_linked(cell, &cell.northeast) {
+            walls.push(((x_ne, y_n), (x_fe, y_m)));
+        }
+
+        if !_hex_linked(cell, &cell.southeast) {
+            walls.push(((x_fe, y_m), (x_ne, y_s)));
+        }
+
+        if !_hex_linked(cell, &cell.south) {
+            walls.push(((x_ne, y_s), (x_nw, y_s)));
+        }
+    }
+
+    for ((ax, ay), (bx, by)) in walls {
+        draw_antialiased_line_segment_mut(
+            &mut img,
+            (ax, ay),
+            (bx, by),
+            style.wall_color,
+            interpolate,
+        );
+    }
+
+    img.save(name).unwrap()
+}
+
+fn _hex_linked(cell: &HexCell, neighbor: &Option<Position>) -> bool {
+    match *neighbor {
+        Some(ref pos) => cell.is_linked_pos(pos),
+        None => false,
+    }
+}
+
+pub fn triangle_png(grid: &TriangleGrid, style: &Style, name: &str) {
+    let offset = 5.0;
+    let size = style.cell_size as f32;
+    let half_width = size / 2.0;
+    let height = size * 3.0_f32.sqrt() / 2.0;
+    let half_height = height / 2.0;
+
+    let img_width = (size * (grid.width + 1) as f32 / 2.0 + offset * 2.0).round() as u32;
+    let img_height = (height * grid.height as f32 + offset * 2.0).round() as u32;
+    let max_weight = grid.cells
+        .iter()
+        .max_by_key(|c| c.weight())
+        .unwrap_or(&TriangleCell::new(0, 0))
+        .weight();
+
+    let mut img = RgbImage::new(img_width, img_height);
+
+    let mut walls = Vec::new();
+
+    // background
+    draw_filled_rect_mut(
+        &mut img,
+        Rect::at(0, 0).of_size(img_width, img_height),
+        style.background_color,
+    );
+
+    for cell in &grid.cells {
+        let pos = cell.pos();
+        let cx = offset + half_width + pos.col as f32 * half_width;
+        let cy = offset + half_height + pos.row as f32 * height;
+
+        let west_x = (cx - half_width) as i32;
+        let mid_x = cx as i32;
+        let east_x = (cx + half_width) as i32;
+
+        let (apex_y, base_y) = if cell.upright() {
+            ((cy - half_height) as i32, (cy + half_height) as i32)
+        } else {
+            ((cy + half_height) as i32, (cy - half_height) as i32)
+        };
+
+        if style.draw_solution || style.color_fn.is_some() {
+            let bounds = vec![
+                Point::new(west_x, base_y),
+                Point::new(mid_x, apex_y),
+                Point::new(east_x, base_y),
+            ];
+
+            if style.draw_solution && cell.in_solution() {
+                draw_convex_polygon_mut(&mut img, bounds.as_slice(), style.solution_color);
+            } else {
+                let color = match style.color_fn {
+                    Some(f) => f(cell.weight(), max_weight),
+                    None => style.background_color,
+                };
+                draw_convex_polygon_mut(&mut img, bounds.as_slice(), color);
+            }
+        }
+
+        // the west wall is only drawn on the border, every other west wall is
+        // the east wall of the neighboring cell
+        if cell.west.is_none() {
+            walls.push(((west_x, base_y), (mid_x, apex_y)));
+        }
+
+        let east_linked = match cell.east {
+            Some(ref east) => cell.is_linked_pos(east),
+            None => false,
+        };
+
+        if !east_linked {
+            walls.push(((east_x, base_y), (mid_x, apex_y)));
+        }
+
+        // the base is shared with the south neighbor of upright cells and the
+        // north neighbor of inverted ones, so only draw it once
+        let no_south = cell.upright() && cell.south.is_none();
+        let not_linked = match cell.north {
+            Some(ref north) => !cell.upright() && !cell.is_linked_pos(north),
+            None => !cell.upright(),
+        };
+
+        if no_south || not_linked {
+            walls.push(((east_x, base_y), (west_x, base_y)));
+        }
+    }
+
+    for ((ax, ay), (bx, by)) in walls {
+        draw_antialiased_line_segment_mut(
+            &mut img,
+            (ax, ay),
+            (bx, by),
+            style.wall_color,
+            interpolate,
+        );
+    }
+
     img.save(name).unwrap()
 }
 
+// levels are drawn left to right, lowest first, with a gap of two cells
+// between them. Passages to the level above are marked with an arrow in the
+// top half of the cell, and passages to the level below in the bottom half
+pub fn grid_3d_png(grid: &Grid3D, style: &Style, name: &str) {
+    grid_3d_image(grid, style).save(name).unwrap()
+}
+
+pub fn grid_3d_image(grid: &Grid3D, style: &Style) -> RgbImage {
+    let t = style.wall_thickness;
+    let cs = style.cell_size;
+    let p = (cs + t) as i32;
+
+    let level_width = grid.width as u32 * (cs + t) + t;
+    let level_height = grid.height as u32 * (cs + t) + t;
+    let gap = 2 * cs;
+
+    // a grid without levels still gets the space of one, left empty
+    let width = grid.levels.max(1) as u32 * (level_width + gap) - gap;
+    let height = level_height;
+    let max_weight = grid.cells
+        .iter()
+        .max_by_key(|c| c.weight())
+        .unwrap_or(&Cell3D::new(0, 0, 0))
+        .weight();
+
+    let mut img = RgbImage::new(width, height);
+
+    // background
+    draw_filled_rect_mut(
+        &mut img,
+        Rect::at(0, 0).of_size(width, height),
+        style.background_color,
+    );
+
+    let color = |cell: &Cell3D| {
+        if style.draw_solution && cell.in_solution() {
+            Some(style.solution_color)
+        } else {
+            style.color_fn.map(|f| f(cell.weight(), max_weight))
+        }
+    };
+
+    let linked = |cell: &Cell3D, other: &Option<Position3D>| match *other {
+        Some(ref pos) => cell.is_linked_pos(pos),
+        None => false,
+    };
+
+    let gap_color = |cell: &Cell3D, other: &Position3D| {
+        let solved = grid.get(other).map_or(false, |o| cell.in_solution() && o.in_solution());
+        if style.draw_solution && solved {
+            Some(style.solution_color)
+        } else {
+            style.color_fn.map(|f| f(cell.weight(), max_weight))
+        }
+    };
+
+    let mut walls = Vec::new();
+
+    for cell in &grid.cells {
+        let pos = cell.pos();
+        let ox = (pos.level as u32 * (level_width + gap)) as i32;
+        let x = ox + pos.col as i32 * p + t as i32;
+        let y = pos.row as i32 * p + t as i32;
+
+        if let Some(c) = color(cell) {
+            draw_filled_rect_mut(&mut img, Rect::at(x, y).of_size(cs, cs), c);
+        }
+
+        // the gaps between linked cells take the color of the cell on the
+        // west or north side, and the solution color only when both cells
+        // are in the solution
+        if let Some(ref east) = cell.east {
+            if cell.is_linked_pos(east) {
+                if let Some(c) = gap_color(cell, east) {
+                    draw_filled_rect_mut(&mut img, Rect::at(x + cs as i32, y).of_size(t, cs), c);
+                }
+            } else {
+                walls.push(Rect::at(x + cs as i32, y - t as i32).of_size(t, cs + 2 * t));
+            }
+        }
+
+        if let Some(ref south) = cell.south {
+            if cell.is_linked_pos(south) {
+                if let Some(c) = gap_color(cell, south) {
+                    draw_filled_rect_mut(&mut img, Rect::at(x, y + cs as i32).of_size(cs, t), c);
+                }
+            } else {
+                walls.push(Rect::at(x - t as i32, y + cs as i32).of_size(cs + 2 * t, t));
+            }
+        }
+
+        let third = cs as i32 / 3;
+        let mid = x + cs as i32 / 2;
+
+        if linked(cell, &cell.up) {
+            let bounds = [
+                Point::new(mid, y + cs as i32 / 8),
+                Point::new(mid + third / 2, y + cs as i32 / 8 + third / 2),
+                Point::new(mid - third / 2, y + cs as i32 / 8 + third / 2),
+            ];
+            draw_convex_polygon_mut(&mut img, &bounds, style.wall_color);
+        }
+
+        if linked(cell, &cell.down) {
+            let bottom = y + cs as i32 - cs as i32 / 8;
+            let bounds = [
+                Point::new(mid, bottom),
+                Point::new(mid - third / 2, bottom - third / 2),
+                Point::new(mid + third / 2, bottom - third / 2),
+            ];
+            draw_convex_polygon_mut(&mut img, &bounds, style.wall_color);
+        }
+    }
+
+    for level in 0..grid.levels {
+        let ox = (level as u32 * (level_width + gap)) as i32;
+
+        // top, left, right, bot
+        walls.push(Rect::at(ox, 0).of_size(level_width, t));
+        walls.push(Rect::at(ox, 0).of_size(t, level_height));
+        walls.push(Rect::at(ox + (level_width - t) as i32, 0).of_size(t, level_height));
+        walls.push(Rect::at(ox, (level_height - t) as i32).of_size(level_width, t));
+    }
+
+    for rect in walls {
+        draw_filled_rect_mut(&mut img, rect, style.wall_color);
+    }
+
+    img
+}
+
 #[cfg(test)]
 mod test_style {
     use super::*;
@@ -391,6 +1089,7 @@ mod test_style {
             color_fn: None,
             draw_solution: false,
             solution_color: Rgb([200, 0, 0]),
+            inset: 0,
         };
 
         assert_eq!(a, expected);
@@ -403,6 +1102,7 @@ mod test_style {
             .color_fn(_test_color_fn)
             .draw_solution()
             .solution_color(&[11, 11, 11])
+            .inset(4)
             .build();
 
         let expected = Style {
@@ -413,8 +1113,42 @@ mod test_style {
             color_fn: Some(_test_color_fn),
             draw_solution: true,
             solution_color: Rgb([11, 11, 11]),
+            inset: 4,
         };
 
         assert_eq!(a, expected);
     }
 }
+
+#[cfg(test)]
+mod test_grid_3d_image {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let style = StyleBuilder::new().build();
+        let img = grid_3d_image(&Grid3D::new(0, 3, 3), &style);
+        let side = 3 * (style.cell_size + style.wall_thickness) + style.wall_thickness;
+        assert_eq!(img.dimensions(), (side, side));
+    }
+
+    #[test]
+    fn solution_gaps() {
+        let style = StyleBuilder::new().draw_solution().build();
+        let a = Position3D::new(0, 0, 0);
+        let b = Position3D::new(0, 0, 1);
+        let c = Position3D::new(0, 0, 2);
+        let mut grid = Grid3D::new(1, 3, 1);
+        grid.link(&a, &b);
+        grid.link(&b, &c);
+        grid.get_mut(&a).unwrap().mark_in_solution();
+        grid.get_mut(&b).unwrap().mark_in_solution();
+
+        // the gaps sit just east of the first two cells, halfway down
+        let img = grid_3d_image(&grid, &style);
+        let p = style.cell_size + style.wall_thickness;
+        let y = style.wall_thickness + style.cell_size / 2;
+        assert_eq!(*img.get_pixel(p, y), style.solution_color);
+        assert_ne!(*img.get_pixel(2 * p, y), style.solution_color);
+    }
+}
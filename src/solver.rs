@@ -1,64 +1,364 @@
+use algorithms::deadends;
 use data::cell::MazeCell;
-use data::grid::{Grid, MazeGrid, PolarGrid};
-use data::pos::Position;
-use std::collections::HashSet;
+use data::grid::{Grid, MazeGrid, PolarGrid, Topology};
+use data::pos::{MazePosition, Position};
+use std::cmp::Reverse;
+use std::collections::{btree_map, BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::f32::consts::PI;
 
-pub fn dijkstra<G: MazeGrid>(grid: &mut G, start: &<G::CellType as MazeCell>::PositionType) {
-    let mut visited = HashSet::new();
-    let mut front: Vec<<G::CellType as MazeCell>::PositionType> = vec![start.clone()];
-    let mut dist = 0;
-
-    while !front.is_empty() {
-        let mut next = Vec::new();
-        for pos in &front {
-            // possible with loops
-            if visited.contains(pos) {
-                continue;
+// how far every reachable cell is from a root cell, kept apart from the grid
+// so that several can be held at once and nothing is left behind in the cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances<P: MazePosition> {
+    root: P,
+    cells: BTreeMap<P, u32>,
+    // the cell each one was first reached from, the root aside
+    previous: BTreeMap<P, P>,
+}
+
+impl<P: MazePosition> Distances<P> {
+    pub fn root(&self) -> &P {
+        &self.root
+    }
+
+    pub fn get(&self, pos: &P) -> Option<u32> {
+        self.cells.get(pos).cloned()
+    }
+
+    pub fn contains(&self, pos: &P) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    // the number of reachable cells, the root included
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, P, u32> {
+        self.cells.iter()
+    }
+
+    // the furthest cell from the root and its distance
+    pub fn max(&self) -> (&P, u32) {
+        self.cells
+            .iter()
+            .max_by_key(|&(_, dist)| *dist)
+            .map(|(pos, dist)| (pos, *dist))
+            .unwrap_or((&self.root, 0))
+    }
+
+    // walks back from the target through the cell each one was reached from
+    pub fn path_to(&self, target: &P) -> Option<Path<P>> {
+        let cost = self.get(target)?;
+        let mut cells = vec![target.clone()];
+        let mut cur = target;
+
+        while let Some(prev) = self.previous.get(cur) {
+            cells.push(prev.clone());
+            cur = prev;
+        }
+
+        cells.reverse();
+        Some(Path { cells, cost })
+    }
+
+    // writes the distances into the cells for the renderers. Cells that can't
+    // be reached are reset to 0
+    pub fn apply_to<G>(&self, grid: &mut G)
+    where
+        G: MazeGrid,
+        G::CellType: MazeCell<PositionType = P>,
+    {
+        for pos in grid.positions() {
+            let dist = self.get(&pos).unwrap_or(0);
+            grid.get_mut(&pos).unwrap().update_weight(dist);
+        }
+    }
+}
+
+// a route through linked cells, from its start to its end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<P: MazePosition> {
+    cells: Vec<P>,
+    cost: u32,
+}
+
+impl<P: MazePosition> Path<P> {
+    pub fn cells(&self) -> &[P] {
+        &self.cells
+    }
+
+    pub fn start(&self) -> &P {
+        &self.cells[0]
+    }
+
+    pub fn end(&self) -> &P {
+        &self.cells[self.cells.len() - 1]
+    }
+
+    // the number of cells on the path, both ends included
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // the total cost of the cells stepped into after the start
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    pub fn contains(&self, pos: &P) -> bool {
+        self.cells.contains(pos)
+    }
+
+    // marks the cells as the solution for the renderers
+    pub fn apply_to<G>(&self, grid: &mut G)
+    where
+        G: MazeGrid,
+        G::CellType: MazeCell<PositionType = P>,
+    {
+        for pos in &self.cells {
+            if let Some(cell) = grid.get_mut(pos) {
+                cell.mark_in_solution();
             }
+        }
+    }
+}
+
+// distances are the cheapest total cost of the cells stepped into on the way
+// from start, which is the number of steps while every cell costs 1. Cells
+// come off the queue cheapest first, so a cell's distance, and the cell it was
+// reached from, are final the first time it is popped
+pub fn distances<G: MazeGrid>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+) -> Distances<<G::CellType as MazeCell>::PositionType> {
+    let mut cells = BTreeMap::new();
+    let mut previous = BTreeMap::new();
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((0u32, start.clone(), None)));
 
-            visited.insert(pos.clone());
-            if let Some(ref mut cell) = grid.get_mut(pos) {
-                cell.update_weight(dist);
-                let mut links = cell.links()
-                    .iter()
-                    .cloned()
-                    .filter(|l| !visited.contains(l))
-                    .collect::<Vec<<G::CellType as MazeCell>::PositionType>>();
+    while let Some(Reverse((dist, pos, from))) = queue.pop() {
+        // possible with loops, or when a cheaper route was found later
+        if cells.contains_key(&pos) {
+            continue;
+        }
+
+        let cell = match grid.get(&pos) {
+            Some(cell) => cell,
+            None => continue,
+        };
+        cells.insert(pos.clone(), dist);
+        if let Some(from) = from {
+            previous.insert(pos.clone(), from);
+        }
 
-                next.append(&mut links);
+        for link in cell.links().iter().filter(|l| !cells.contains_key(*l)) {
+            if let Some(next) = grid.get(link) {
+                let cost = dist.saturating_add(next.cost());
+                queue.push(Reverse((cost, link.clone(), Some(pos.clone()))));
             }
         }
-        front = next;
-        dist += 1;
     }
+
+    Distances {
+        root: start.clone(),
+        cells,
+        previous,
+    }
+}
+
+pub fn shortest_path<G: MazeGrid>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+    target: &<G::CellType as MazeCell>::PositionType,
+) -> Option<Path<<G::CellType as MazeCell>::PositionType>> {
+    distances(grid, start).path_to(target)
 }
 
+// the same as distances, but written into the cells
+pub fn dijkstra<G: MazeGrid>(grid: &mut G, start: &<G::CellType as MazeCell>::PositionType) {
+    distances(grid, start).apply_to(grid);
+}
+
+// writes the distances from start into the cells and marks the path to target
 pub fn solve<G: MazeGrid>(
     grid: &mut G,
     start: &<G::CellType as MazeCell>::PositionType,
     target: &<G::CellType as MazeCell>::PositionType,
 ) {
-    dijkstra(grid, start);
+    let distances = distances(grid, start);
+    distances.apply_to(grid);
+    if let Some(path) = distances.path_to(target) {
+        path.apply_to(grid);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search<P: MazePosition> {
+    pub path: Path<P>,
+    // how many cells were taken off the queue, which is the work saved over
+    // flooding the whole grid with dijkstra
+    pub expanded: usize,
+}
+
+// finds the cheapest route from start to goal without touching the grid,
+// guided by a heuristic that estimates the steps remaining. Each step costs
+// at least as much as the cheapest cell, so that is what the estimate is
+// weighed by, and the route is only the cheapest one if it never overestimates
+pub fn astar<G, F>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+    goal: &<G::CellType as MazeCell>::PositionType,
+    heuristic: F,
+) -> Option<Search<<G::CellType as MazeCell>::PositionType>>
+where
+    G: MazeGrid,
+    F: Fn(
+        &G,
+        &<G::CellType as MazeCell>::PositionType,
+        &<G::CellType as MazeCell>::PositionType,
+    ) -> u32,
+{
+    grid.get(start)?;
+    let cheapest = grid.cells().iter().map(|c| c.cost()).min().unwrap_or(0);
+    let estimate = |pos| heuristic(grid, pos, goal).saturating_mul(cheapest);
+
+    let mut best = HashMap::new();
+    let mut came_from: HashMap<
+        <G::CellType as MazeCell>::PositionType,
+        <G::CellType as MazeCell>::PositionType,
+    > = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    let mut expanded = 0;
 
-    let mut next = Some(target.clone());
+    // ties go to the cell furthest along, which is usually closer to the goal
+    best.insert(start.clone(), 0);
+    queue.push(Reverse((estimate(start), Reverse(0), start.clone())));
 
-    while let Some(cur) = next.clone() {
-        next = None;
-        if let Some(ref mut cur) = grid.get_mut(&cur) {
-            cur.mark_in_solution();
+    while let Some(Reverse((_, Reverse(cost), pos))) = queue.pop() {
+        // a cheaper way here was found after this one was queued
+        if best.get(&pos).map_or(false, |&b| cost > b) {
+            continue;
         }
+        expanded += 1;
 
-        if let Some(ref cur) = grid.get(&cur) {
-            for link in cur.links() {
-                if let Some(ref cell) = grid.get(&link) {
-                    if cell.weight() < cur.weight() {
-                        next = Some(link.clone());
-                        break;
-                    }
-                }
+        if pos == *goal {
+            let mut cells = vec![pos];
+            while let Some(prev) = came_from.get(&cells[cells.len() - 1]) {
+                cells.push(prev.clone());
             }
+            cells.reverse();
+            return Some(Search {
+                path: Path { cells, cost },
+                expanded,
+            });
         }
+
+        for link in grid.get(&pos).unwrap().links() {
+            let next = match grid.get(link) {
+                Some(cell) => cost.saturating_add(cell.cost()),
+                None => continue,
+            };
+
+            if best.get(link).map_or(true, |&b| next < b) {
+                best.insert(link.clone(), next);
+                came_from.insert(link.clone(), pos.clone());
+                let total = next.saturating_add(estimate(link));
+                queue.push(Reverse((total, Reverse(next), link.clone())));
+            }
+        }
+    }
+
+    None
+}
+
+// steps between two cells if there were no walls, taking the shorter way
+// around any wrapped seams
+pub fn manhattan(grid: &Grid, a: &Position, b: &Position) -> u32 {
+    let wrap_cols = grid.topology != Topology::Plane && grid.width > 2;
+    let wrap_rows = grid.topology == Topology::Torus && grid.height > 2;
+
+    let along = |x: usize, y: usize, size: usize, wraps: bool| {
+        let d = if x > y { x - y } else { y - x };
+        if wraps {
+            d.min(size - d)
+        } else {
+            d
+        }
+    };
+
+    (along(a.row, b.row, grid.height, wrap_rows) + along(a.col, b.col, grid.width, wrap_cols))
+        as u32
+}
+
+// every step moves at most one ring in or out, and turns at most as far as
+// the width of the cells in the first ring, which are the widest. Going
+// through the center is the only way to turn further
+pub fn polar_distance(grid: &PolarGrid, a: &Position, b: &Position) -> u32 {
+    let rings = if a.row > b.row { a.row - b.row } else { b.row - a.row };
+    if a.row == 0 || b.row == 0 || grid.column_counts.len() < 2 {
+        return rings as u32;
+    }
+
+    let angle = |pos: &Position| {
+        (pos.col as f32 + 0.5) * 2.0 * PI / grid.column_counts[pos.row] as f32
+    };
+    let mut turn = (angle(a) - angle(b)).abs();
+    if turn > PI {
+        turn = 2.0 * PI - turn;
+    }
+
+    let widest = 2.0 * PI / grid.column_counts[1] as f32;
+    // a little slack so rounding can't push the estimate past a real route
+    let around = (turn / widest - 1e-3).ceil().max(0.0) as usize;
+    rings.max(around.min(a.row + b.row)) as u32
+}
+
+// the path between the two cells furthest apart anywhere in the grid, or None
+// when it has no cells. In a tree where every step costs the same, the cell
+// furthest from anywhere is one end of the longest path, so two searches for
+// each group of connected cells are enough. Loops or uneven costs break that,
+// and every cell gets searched from instead
+pub fn longest_path<G: MazeGrid>(
+    grid: &G,
+) -> Option<Path<<G::CellType as MazeCell>::PositionType>> {
+    let positions = grid.positions();
+    let links = grid.cells().iter().map(|c| c.links().len()).sum::<usize>() / 2;
+    let uniform = grid.cells().windows(2).all(|w| w[0].cost() == w[1].cost());
+
+    let mut seen = HashSet::new();
+    let mut furthest = Vec::new();
+    for pos in &positions {
+        if seen.contains(pos) {
+            continue;
+        }
+        let group = distances(grid, pos);
+        seen.extend(group.iter().map(|(p, _)| p.clone()));
+        furthest.push(group.max().0.clone());
     }
+
+    let tree = links + furthest.len() == positions.len();
+    let starts = if tree && uniform { furthest } else { positions };
+
+    let mut longest = None;
+    for start in &starts {
+        let from_start = distances(grid, start);
+        let (end, dist) = from_start.max();
+        let end = end.clone();
+        if longest.as_ref().map_or(true, |&(_, _, max)| dist > max) {
+            longest = Some((from_start, end, dist));
+        }
+    }
+
+    longest.and_then(|(from_start, end, _)| from_start.path_to(&end))
 }
 
 pub fn furthest_on_rim(grid: &mut PolarGrid, from: &Position) -> Position {
@@ -81,6 +381,14 @@ pub fn furthest_on_rim(grid: &mut PolarGrid, from: &Position) -> Position {
 }
 
 pub fn furthest_corners(grid: &mut Grid) -> (Position, Position) {
+    // corners mean nothing once the edges wrap, so fall back to finding the
+    // furthest cell from a corner and then the furthest cell from that one
+    if grid.topology != Topology::Plane {
+        let first = _furthest(grid, &Position::new(0, 0));
+        let second = _furthest(grid, &first);
+        return (first, second);
+    }
+
     let mut candidates = Vec::new();
 
     let corners = vec![
@@ -96,7 +404,7 @@ pub fn furthest_corners(grid: &mut Grid) -> (Position, Position) {
 
     for corner in &corners {
         dijkstra(grid, &corner.pos);
-        let max = vec![
+        let max = *[
             grid.get(&Position::new(0, 0)).unwrap(),
             grid.get(&Position::new(0, grid.width - 1)).unwrap(),
             grid.get(&Position::new(grid.height - 1, 0)).unwrap(),
@@ -104,8 +412,7 @@ pub fn furthest_corners(grid: &mut Grid) -> (Position, Position) {
                 .unwrap(),
         ].iter()
             .max_by_key(|c| c.weight())
-            .unwrap()
-            .clone();
+            .unwrap();
         candidates.push((corner.pos.clone(), max.pos.clone(), max.weight()));
     }
 
@@ -114,3 +421,722 @@ pub fn furthest_corners(grid: &mut Grid) -> (Position, Position) {
     let (pos1, pos2, _) = candidates[candidates.len() - 1].clone();
     (pos1, pos2)
 }
+
+fn _furthest(grid: &mut Grid, from: &Position) -> Position {
+    dijkstra(grid, from);
+
+    grid.cells
+        .iter()
+        .max_by_key(|c| c.weight())
+        .unwrap()
+        .pos
+        .clone()
+}
+
+// how a person might get through the maze: every cell they step into after
+// the start, in order and with revisits, and the route they came away with.
+// Dead-end filling never walks, so its visits are the cells it fills in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walk<P: MazePosition> {
+    pub visits: Vec<P>,
+    pub path: Option<Path<P>>,
+}
+
+impl<P: MazePosition> Walk<P> {
+    pub fn steps(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn solved(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+// drops every detour from a walk, leaving the route from start to where the
+// walk stopped without ever passing through a cell twice
+fn _route<G: MazeGrid>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+    visits: &[<G::CellType as MazeCell>::PositionType],
+) -> Path<<G::CellType as MazeCell>::PositionType> {
+    let mut cells = vec![start.clone()];
+    for pos in visits {
+        match cells.iter().position(|p| p == pos) {
+            Some(i) => cells.truncate(i + 1),
+            None => cells.push(pos.clone()),
+        }
+    }
+
+    let cost = cells[1..]
+        .iter()
+        .filter_map(|p| grid.get(p))
+        .fold(0u32, |cost, cell| cost.saturating_add(cell.cost()));
+    Path { cells, cost }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+// grids that know which way round a cell its neighbors lie, which is what it
+// takes to keep a hand on the wall
+pub trait Clockwise: MazeGrid {
+    fn clockwise(
+        &self,
+        pos: &<Self::CellType as MazeCell>::PositionType,
+    ) -> Vec<<Self::CellType as MazeCell>::PositionType>;
+}
+
+impl Clockwise for Grid {
+    fn clockwise(&self, pos: &Position) -> Vec<Position> {
+        match self.get(pos) {
+            Some(cell) => [&cell.north, &cell.east, &cell.south, &cell.west]
+                .iter()
+                .filter_map(|&side| side.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+// columns count up clockwise, so the outward cells come in order between the
+// ccw and cw sides
+impl Clockwise for PolarGrid {
+    fn clockwise(&self, pos: &Position) -> Vec<Position> {
+        let cell = match self.get(pos) {
+            Some(cell) => cell,
+            None => return Vec::new(),
+        };
+
+        let mut around = Vec::new();
+        let sides = cell.ccw.iter().chain(&cell.outward).chain(&cell.cw).chain(&cell.inward);
+        for side in sides {
+            // the two cells of a narrow ring are each other's cw and ccw
+            if !around.contains(side) {
+                around.push(side.clone());
+            }
+        }
+        around
+    }
+}
+
+// walks with one hand on the wall, which always turns the way of that hand
+// when it can. Mazes with loops can send it round in circles without ever
+// reaching the end, in which case it stops the first time it repeats a step
+pub fn wall_follower<G: Clockwise>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+    end: &<G::CellType as MazeCell>::PositionType,
+    hand: Hand,
+) -> Walk<<G::CellType as MazeCell>::PositionType> {
+    let mut visits = Vec::new();
+    let mut taken = HashSet::new();
+    let mut pos = start.clone();
+    let mut from = None;
+
+    while pos != *end {
+        let exits = grid.clockwise(&pos)
+            .into_iter()
+            .filter(|p| grid.get(&pos).map_or(false, |c| c.is_linked_pos(p)))
+            .collect::<Vec<_>>();
+        if exits.is_empty() {
+            break;
+        }
+
+        // the way in is always one of the exits, and turning with the hand
+        // means taking the next one round from it
+        let next = match from.as_ref().and_then(|f| exits.iter().position(|p| p == f)) {
+            Some(i) if hand == Hand::Left => exits[(i + 1) % exits.len()].clone(),
+            Some(i) => exits[(i + exits.len() - 1) % exits.len()].clone(),
+            None => exits[0].clone(),
+        };
+        if !taken.insert((pos.clone(), next.clone())) {
+            break;
+        }
+
+        visits.push(next.clone());
+        from = Some(pos);
+        pos = next;
+    }
+
+    let path = if pos == *end { Some(_route(grid, start, &visits)) } else { None };
+    Walk { visits, path }
+}
+
+fn _passage<P: MazePosition>(a: &P, b: &P) -> (P, P) {
+    if a < b {
+        (a.clone(), b.clone())
+    } else {
+        (b.clone(), a.clone())
+    }
+}
+
+// marks every passage each time it is walked and never takes one marked
+// twice. Coming back into a cell seen before along a fresh passage means
+// turning straight round, otherwise the least walked way on is taken, ties
+// going to the first linked cell other than the way in
+pub fn tremaux<G: MazeGrid>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+    end: &<G::CellType as MazeCell>::PositionType,
+) -> Walk<<G::CellType as MazeCell>::PositionType> {
+    let mut visits = Vec::new();
+    let mut marks = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut pos = start.clone();
+    let mut from = None;
+    let mut revisit = false;
+    seen.insert(pos.clone());
+
+    while pos != *end {
+        let next = {
+            let marked = |p: &_| marks.get(&_passage(&pos, p)).cloned().unwrap_or(0);
+            match from {
+                Some(ref f) if revisit && marked(f) == 1 => Some(f.clone()),
+                _ => grid.links(&pos)
+                    .into_iter()
+                    .filter(|p| marked(p) < 2)
+                    .min_by_key(|p| (marked(p), from.as_ref() == Some(p))),
+            }
+        };
+        let next = match next {
+            Some(next) => next,
+            None => break,
+        };
+
+        *marks.entry(_passage(&pos, &next)).or_insert(0) += 1;
+        revisit = !seen.insert(next.clone());
+        visits.push(next.clone());
+        from = Some(pos);
+        pos = next;
+    }
+
+    let path = if pos == *end { Some(_route(grid, start, &visits)) } else { None };
+    Walk { visits, path }
+}
+
+// fills in dead ends one cell at a time, starting from algorithms::deadends,
+// until every cell left has two ways out or is the start or end. What is left
+// of a perfect maze is the path between them, and of a braided one the cells
+// on some route between them, so the path is the fewest steps through the
+// cells that weren't filled
+pub fn dead_end_filling<G: MazeGrid>(
+    grid: &G,
+    start: &<G::CellType as MazeCell>::PositionType,
+    end: &<G::CellType as MazeCell>::PositionType,
+) -> Walk<<G::CellType as MazeCell>::PositionType> {
+    let mut visits = Vec::new();
+    let mut filled = HashSet::new();
+    let mut queue = deadends(grid).into_iter().collect::<VecDeque<_>>();
+
+    while let Some(pos) = queue.pop_front() {
+        let open = grid.links(&pos)
+            .into_iter()
+            .filter(|p| !filled.contains(p))
+            .collect::<Vec<_>>();
+        if pos == *start || pos == *end || open.len() > 1 || !filled.insert(pos.clone()) {
+            continue;
+        }
+
+        visits.push(pos);
+        queue.extend(open);
+    }
+
+    // a search through what is left, walking back from end once it is found
+    let mut previous = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(start.clone());
+    previous.insert(start.clone(), start.clone());
+    while let Some(pos) = queue.pop_front() {
+        if pos == *end {
+            break;
+        }
+        for link in grid.links(&pos) {
+            if !filled.contains(&link) && !previous.contains_key(&link) {
+                previous.insert(link.clone(), pos.clone());
+                queue.push_back(link);
+            }
+        }
+    }
+
+    let path = previous.get(end).map(|_| {
+        let mut cells = vec![end.clone()];
+        while cells[cells.len() - 1] != *start {
+            let prev = previous[&cells[cells.len() - 1]].clone();
+            cells.push(prev);
+        }
+        cells.reverse();
+        _route(grid, start, &cells[1..])
+    });
+
+    Walk { visits, path }
+}
+
+#[cfg(test)]
+mod test_solver {
+    use super::*;
+    use algorithms::{braid, recursive_backtracker};
+    use data::grid::HexGrid;
+    use rand::{Rng, SeedableRng, StdRng};
+
+    fn solution(grid: &Grid) -> Vec<Position> {
+        grid.cells
+            .iter()
+            .filter(|c| c.in_solution())
+            .map(|c| c.pos.clone())
+            .collect()
+    }
+
+    // a 3x3 grid with every passage open, so that there are many ways around
+    fn open_grid() -> Grid {
+        let mut grid = Grid::new(3, 3);
+        for row in 0..3 {
+            for col in 0..3 {
+                let pos = Position::new(row, col);
+                if col < 2 {
+                    grid.link(&pos, &Position::new(row, col + 1));
+                }
+                if row < 2 {
+                    grid.link(&pos, &Position::new(row + 1, col));
+                }
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn unit_costs_count_steps() {
+        let mut grid = open_grid();
+        dijkstra(&mut grid, &Position::new(0, 0));
+
+        for cell in &grid.cells {
+            assert_eq!(cell.weight() as usize, cell.pos.row + cell.pos.col);
+        }
+    }
+
+    #[test]
+    fn costly_cells_are_avoided() {
+        let mut grid = open_grid();
+        let start = Position::new(1, 0);
+        let end = Position::new(1, 2);
+
+        solve(&mut grid, &start, &end);
+        assert_eq!(solution(&grid).len(), 3);
+        assert_eq!(grid.get(&end).unwrap().weight(), 2);
+
+        // lava in the middle pushes the path around the top
+        let mut grid = open_grid();
+        grid.get_mut(&Position::new(1, 1)).unwrap().update_cost(10);
+        grid.get_mut(&Position::new(2, 1)).unwrap().update_cost(10);
+        solve(&mut grid, &start, &end);
+
+        let expected = vec![
+            Position::new(0, 0),
+            Position::new(0, 1),
+            Position::new(0, 2),
+            start.clone(),
+            end.clone(),
+        ];
+        assert_eq!(solution(&grid), expected);
+        assert_eq!(grid.get(&end).unwrap().weight(), 4);
+    }
+
+    #[test]
+    fn free_cells() {
+        let mut grid = open_grid();
+        for cell in grid.cells.iter_mut() {
+            cell.update_cost(0);
+        }
+        grid.get_mut(&Position::new(2, 2)).unwrap().update_cost(1);
+
+        solve(&mut grid, &Position::new(0, 0), &Position::new(2, 2));
+        assert_eq!(grid.get(&Position::new(2, 2)).unwrap().weight(), 1);
+        assert!(grid.get(&Position::new(0, 0)).unwrap().in_solution());
+    }
+
+    // a corridor round three sides of a square, where the last two cells are
+    // free and so tie with the cell before them
+    fn free_corridor() -> Grid {
+        let mut grid = Grid::new(2, 2);
+        grid.link(&Position::new(0, 0), &Position::new(1, 0));
+        grid.link(&Position::new(1, 0), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(0, 1));
+        grid.get_mut(&Position::new(1, 1)).unwrap().update_cost(0);
+        grid.get_mut(&Position::new(0, 1)).unwrap().update_cost(0);
+        grid
+    }
+
+    #[test]
+    fn free_cells_keep_their_paths() {
+        let grid = free_corridor();
+        let start = Position::new(0, 0);
+        let dists = distances(&grid, &start);
+        assert_eq!(dists.get(&Position::new(1, 1)), Some(1));
+        assert_eq!(dists.get(&Position::new(0, 1)), Some(1));
+
+        let path = shortest_path(&grid, &start, &Position::new(1, 1)).unwrap();
+        assert_eq!(path.cells(), &[start.clone(), Position::new(1, 0), Position::new(1, 1)]);
+        assert_eq!(path.cost(), 1);
+
+        let path = dists.path_to(&Position::new(0, 1)).unwrap();
+        assert_eq!((path.len(), path.cost()), (4, 1));
+        assert_eq!(dists.path_to(&start).unwrap().cells(), &[start]);
+    }
+
+    #[test]
+    fn free_cells_are_solved() {
+        let mut grid = free_corridor();
+        solve(&mut grid, &Position::new(0, 0), &Position::new(1, 1));
+        assert_eq!(
+            solution(&grid),
+            vec![Position::new(0, 0), Position::new(1, 0), Position::new(1, 1)]
+        );
+        assert_eq!(grid.get(&Position::new(0, 1)).unwrap().weight(), 1);
+    }
+
+    #[test]
+    fn astar_matches_solve() {
+        let mut rng = StdRng::from_seed([6; 32]);
+        let mut grid = Grid::new(15, 15);
+        recursive_backtracker(&mut grid, &mut rng);
+        braid(&mut grid, 0.5, &mut rng);
+        for _ in 0..20 {
+            let pos = grid.random_pos_with_rng(&mut rng).unwrap();
+            grid.get_mut(&pos).unwrap().update_cost(5);
+        }
+
+        let start = Position::new(0, 0);
+        let goal = Position::new(14, 14);
+        let found = astar(&grid, &start, &goal, manhattan).unwrap();
+        let flooded = astar(&grid, &start, &goal, |_, _, _| 0).unwrap();
+
+        solve(&mut grid, &start, &goal);
+        assert_eq!(found.path.cost(), grid.get(&goal).unwrap().weight());
+        assert_eq!(flooded.path.cost(), found.path.cost());
+        assert!(found.expanded <= flooded.expanded);
+
+        // the path steps through linked cells and adds up to its cost
+        assert_eq!(*found.path.start(), start);
+        assert_eq!(*found.path.end(), goal);
+        let mut cost = 0;
+        for pair in found.path.cells().windows(2) {
+            assert!(grid.get(&pair[0]).unwrap().is_linked_pos(&pair[1]));
+            cost += grid.get(&pair[1]).unwrap().cost();
+        }
+        assert_eq!(cost, found.path.cost());
+    }
+
+    #[test]
+    fn astar_through_free_cells() {
+        // the way along the bottom is longer but costs nothing until the goal
+        let mut grid = Grid::new(3, 2);
+        let start = Position::new(0, 0);
+        let goal = Position::new(0, 2);
+        let bottom = [Position::new(1, 0), Position::new(1, 1), Position::new(1, 2)];
+        grid.link(&start, &Position::new(0, 1));
+        grid.link(&Position::new(0, 1), &goal);
+        grid.link(&start, &bottom[0]);
+        grid.link(&bottom[0], &bottom[1]);
+        grid.link(&bottom[1], &bottom[2]);
+        grid.link(&bottom[2], &goal);
+        for pos in &bottom {
+            grid.get_mut(pos).unwrap().update_cost(0);
+        }
+
+        let found = astar(&grid, &start, &goal, manhattan).unwrap();
+        assert_eq!(found.path.cost(), 1);
+        assert_eq!(found.path.cost(), shortest_path(&grid, &start, &goal).unwrap().cost());
+    }
+
+    #[test]
+    fn astar_unreachable() {
+        let grid = open_grid();
+        let mut walled = Grid::new(2, 1);
+        assert_eq!(astar(&walled, &Position::new(0, 0), &Position::new(0, 1), manhattan), None);
+        assert_eq!(astar(&grid, &Position::new(5, 5), &Position::new(0, 1), manhattan), None);
+
+        walled.link(&Position::new(0, 0), &Position::new(0, 1));
+        let found = astar(&walled, &Position::new(0, 0), &Position::new(0, 0), manhattan).unwrap();
+        assert_eq!(found.path.cells(), &[Position::new(0, 0)]);
+        assert_eq!((found.path.cost(), found.expanded), (0, 1));
+    }
+
+    #[test]
+    fn manhattan_wraps() {
+        let grid = Grid::with_topology(10, 10, Topology::Torus);
+        assert_eq!(manhattan(&grid, &Position::new(0, 0), &Position::new(9, 8)), 3);
+        let grid = Grid::with_topology(10, 10, Topology::Cylinder);
+        assert_eq!(manhattan(&grid, &Position::new(0, 0), &Position::new(9, 8)), 11);
+    }
+
+    #[test]
+    fn polar_distance_never_overestimates() {
+        let mut rng = StdRng::from_seed([7; 32]);
+        let mut grid = PolarGrid::new(8);
+        recursive_backtracker(&mut grid, &mut rng);
+        braid(&mut grid, 1.0, &mut rng);
+
+        for _ in 0..30 {
+            let start = grid.random_pos_with_rng(&mut rng).unwrap();
+            let goal = grid.random_pos_with_rng(&mut rng).unwrap();
+            dijkstra(&mut grid, &start);
+
+            let cheapest = grid.get(&goal).unwrap().weight();
+            assert!(polar_distance(&grid, &start, &goal) <= cheapest);
+            let found = astar(&grid, &start, &goal, polar_distance).unwrap();
+            assert_eq!(found.path.cost(), cheapest);
+        }
+
+        // with every wall knocked down the estimate should still hold
+        let mut open = PolarGrid::new(8);
+        let positions = open.cells.iter().map(|c| c.pos.clone()).collect::<Vec<Position>>();
+        for pos in &positions {
+            for other in open.neighbors(pos) {
+                open.link(pos, &other);
+            }
+        }
+        for start in &positions {
+            dijkstra(&mut open, start);
+            for goal in &positions {
+                let cheapest = open.get(goal).unwrap().weight();
+                assert!(polar_distance(&open, start, goal) <= cheapest);
+            }
+        }
+
+        let pos = Position::new(rng.gen_range(1, 8), 0);
+        assert_eq!(polar_distance(&grid, &pos, &Position::new(0, 0)) as usize, pos.row);
+    }
+
+    #[test]
+    fn distances_leave_the_grid_alone() {
+        let mut rng = StdRng::from_seed([8; 32]);
+        let mut grid = Grid::new(6, 6);
+        recursive_backtracker(&mut grid, &mut rng);
+        braid(&mut grid, 0.5, &mut rng);
+        let before = grid.to_string(true);
+
+        let from_corner = distances(&grid, &Position::new(0, 0));
+        let from_center = distances(&grid, &Position::new(3, 3));
+        assert_eq!(grid.to_string(true), before);
+        assert_eq!(from_corner.len(), 36);
+        assert_eq!(from_corner.get(&Position::new(0, 0)), Some(0));
+        assert_eq!(from_center.root(), &Position::new(3, 3));
+
+        // writing them in matches what dijkstra leaves behind
+        from_center.apply_to(&mut grid);
+        let applied = grid.to_string(true);
+        dijkstra(&mut grid, &Position::new(3, 3));
+        assert_eq!(grid.to_string(true), applied);
+
+        let (furthest, max) = from_corner.max();
+        assert_eq!(from_corner.get(furthest), Some(max));
+        assert!(from_corner.iter().all(|(_, &d)| d <= max));
+    }
+
+    #[test]
+    fn paths() {
+        let mut grid = open_grid();
+        grid.get_mut(&Position::new(1, 1)).unwrap().update_cost(10);
+        let start = Position::new(1, 0);
+        let end = Position::new(1, 2);
+
+        let path = shortest_path(&grid, &start, &end).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.cost(), 4);
+        assert_eq!((path.start(), path.end()), (&start, &end));
+        assert!(!path.contains(&Position::new(1, 1)));
+        assert!(grid.cells.iter().all(|c| !c.in_solution()));
+
+        path.apply_to(&mut grid);
+        assert_eq!(solution(&grid).len(), 5);
+
+        // cells that can't be reached have no distance and no path
+        let mut walled = Grid::new(2, 1);
+        let dists = distances(&walled, &Position::new(0, 0));
+        assert_eq!(dists.get(&Position::new(0, 1)), None);
+        assert_eq!(dists.path_to(&Position::new(0, 1)), None);
+        assert_eq!(dists.max(), (&Position::new(0, 0), 0));
+
+        walled.get_mut(&Position::new(0, 1)).unwrap().update_weight(7);
+        dists.apply_to(&mut walled);
+        assert_eq!(walled.get(&Position::new(0, 1)).unwrap().weight(), 0);
+    }
+
+    fn brute_force<G: MazeGrid>(grid: &G) -> u32 {
+        grid.positions().iter().map(|p| distances(grid, p).max().1).max().unwrap()
+    }
+
+    #[test]
+    fn longest_paths() {
+        let mut rng = StdRng::from_seed([6; 32]);
+        let mut grid = Grid::new(12, 12);
+        recursive_backtracker(&mut grid, &mut rng);
+        let path = longest_path(&grid).unwrap();
+        assert_eq!(path.cost(), brute_force(&grid));
+        let shortest = shortest_path(&grid, path.start(), path.end()).unwrap();
+        assert_eq!(shortest.cost(), path.cost());
+
+        // at least as hard as anything between the corners
+        let (start, end) = furthest_corners(&mut grid);
+        assert!(path.cost() >= shortest_path(&grid, &start, &end).unwrap().cost());
+
+        braid(&mut grid, 0.7, &mut rng);
+        assert_eq!(longest_path(&grid).unwrap().cost(), brute_force(&grid));
+
+        let lava = path.cells()[path.len() / 2].clone();
+        grid.get_mut(&lava).unwrap().update_cost(40);
+        assert_eq!(longest_path(&grid).unwrap().cost(), brute_force(&grid));
+
+        let mut polar = PolarGrid::new(7);
+        recursive_backtracker(&mut polar, &mut rng);
+        let path = longest_path(&polar).unwrap();
+        assert_eq!(path.cost(), brute_force(&polar));
+        let rim = furthest_on_rim(&mut polar, &Position::new(0, 0));
+        let center = Position::new(0, 0);
+        assert!(path.cost() >= shortest_path(&polar, &center, &rim).unwrap().cost());
+
+        let mut hex = HexGrid::new(8, 8);
+        recursive_backtracker(&mut hex, &mut rng);
+        assert_eq!(longest_path(&hex).unwrap().cost(), brute_force(&hex));
+    }
+
+    #[test]
+    fn longest_path_through_free_cells() {
+        let grid = free_corridor();
+        let path = longest_path(&grid).unwrap();
+        assert_eq!(path.cost(), brute_force(&grid));
+        assert_eq!(path.len(), 4);
+
+        // every cell free, where any path at all is as long as the next
+        let mut rng = StdRng::from_seed([8; 32]);
+        let mut grid = Grid::new(8, 8);
+        recursive_backtracker(&mut grid, &mut rng);
+        braid(&mut grid, 0.5, &mut rng);
+        for cell in grid.cells.iter_mut() {
+            cell.update_cost(0);
+        }
+        let path = longest_path(&grid).unwrap();
+        assert_eq!(path.cost(), 0);
+        assert!(path.cells().windows(2).all(|w| grid.get(&w[0]).unwrap().is_linked_pos(&w[1])));
+    }
+
+    #[test]
+    fn longest_path_of_split_grids() {
+        // two corridors that never meet, the second one longer
+        let mut grid = Grid::new(4, 2);
+        grid.link(&Position::new(0, 0), &Position::new(0, 1));
+        for col in 0..3 {
+            grid.link(&Position::new(1, col), &Position::new(1, col + 1));
+        }
+
+        let path = longest_path(&grid).unwrap();
+        assert_eq!((path.len(), path.cost()), (4, 3));
+        assert!(path.contains(&Position::new(1, 0)) && path.contains(&Position::new(1, 3)));
+
+        // a lone cell is its own longest path
+        let single = Grid::new(1, 1);
+        assert_eq!(longest_path(&single).unwrap().len(), 1);
+    }
+
+    // every step of a walk has to go through an open passage
+    fn walked<G: MazeGrid>(grid: &G, start: &Position, walk: &Walk<Position>) -> bool
+    where
+        G::CellType: MazeCell<PositionType = Position>,
+    {
+        let mut pos = start;
+        walk.visits.iter().all(|next| {
+            let linked = grid.get(pos).unwrap().is_linked_pos(next);
+            pos = next;
+            linked
+        })
+    }
+
+    #[test]
+    fn hands_on_the_wall() {
+        // a plus with the start at the top, walked into facing south
+        let mut grid = Grid::new(3, 3);
+        let center = Position::new(1, 1);
+        for arm in &[(0, 1), (1, 2), (2, 1), (1, 0)] {
+            grid.link(&center, &Position::new(arm.0, arm.1));
+        }
+        let (start, end) = (Position::new(0, 1), Position::new(1, 2));
+
+        let left = wall_follower(&grid, &start, &end, Hand::Left);
+        assert_eq!(left.visits, vec![center.clone(), end.clone()]);
+
+        let right = wall_follower(&grid, &start, &end, Hand::Right);
+        assert_eq!(right.steps(), 6);
+        assert_eq!(right.visits[1], Position::new(1, 0));
+        assert_eq!(right.path, left.path);
+        assert_eq!(left.path.unwrap().cells(), &[start, center.clone(), end]);
+
+        // with no walls inside, the middle can never be reached from the edge
+        let grid = open_grid();
+        let walk = wall_follower(&grid, &Position::new(0, 0), &center, Hand::Left);
+        assert!(!walk.solved() && !walk.visits.contains(&center));
+        assert!(tremaux(&grid, &Position::new(0, 0), &center).solved());
+    }
+
+    #[test]
+    fn human_solvers() {
+        let mut rng = StdRng::from_seed([7; 32]);
+        let mut grid = Grid::new(12, 12);
+        recursive_backtracker(&mut grid, &mut rng);
+        let (start, end) = (Position::new(0, 0), Position::new(11, 11));
+        let shortest = shortest_path(&grid, &start, &end);
+
+        // a perfect maze only has the one path, and no passage is walked more
+        // than once in each direction
+        for walk in &[
+            wall_follower(&grid, &start, &end, Hand::Left),
+            wall_follower(&grid, &start, &end, Hand::Right),
+            tremaux(&grid, &start, &end),
+        ] {
+            assert_eq!(walk.path, shortest);
+            assert!(walked(&grid, &start, walk));
+            assert!(walk.steps() <= 2 * (grid.cells.len() - 1));
+        }
+
+        let filling = dead_end_filling(&grid, &start, &end);
+        assert_eq!(filling.path, shortest);
+        assert_eq!(filling.steps() + shortest.unwrap().len(), grid.cells.len());
+
+        braid(&mut grid, 0.5, &mut rng);
+        let shortest = shortest_path(&grid, &start, &end).unwrap();
+        let walk = tremaux(&grid, &start, &end);
+        assert!(walked(&grid, &start, &walk));
+        assert_eq!(walk.path.unwrap().end(), &end);
+
+        // the path only goes through what was left unfilled, and every cell
+        // costs 1 so fewest steps is also cheapest
+        let filling = dead_end_filling(&grid, &start, &end);
+        let path = filling.path.clone().unwrap();
+        assert!(shortest.cells().iter().all(|p| !filling.visits.contains(p)));
+        assert!(path.cells().iter().all(|p| !filling.visits.contains(p)));
+        assert_eq!((path.start(), path.end()), (&start, &end));
+        assert_eq!(path.cost(), shortest.cost());
+
+        let mut polar = PolarGrid::new(6);
+        recursive_backtracker(&mut polar, &mut rng);
+        let (start, end) = (Position::new(0, 0), Position::new(5, 7));
+        let shortest = shortest_path(&polar, &start, &end);
+        for hand in &[Hand::Left, Hand::Right] {
+            let walk = wall_follower(&polar, &start, &end, *hand);
+            assert_eq!(walk.path, shortest);
+            assert!(walked(&polar, &start, &walk));
+        }
+        assert_eq!(tremaux(&polar, &start, &end).path, shortest);
+        assert_eq!(dead_end_filling(&polar, &start, &end).path, shortest);
+    }
+
+    #[test]
+    fn unreachable_ends() {
+        let grid = Grid::new(2, 1);
+        let (start, end) = (Position::new(0, 0), Position::new(0, 1));
+        assert_eq!(wall_follower(&grid, &start, &end, Hand::Left).steps(), 0);
+        assert!(!tremaux(&grid, &start, &end).solved());
+        assert!(!dead_end_filling(&grid, &start, &end).solved());
+    }
+}
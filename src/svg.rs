@@ -0,0 +1,264 @@
+use data::cell::{Cell, MazeCell};
+use data::grid::{Grid, PolarGrid};
+use data::pos::Position;
+use image::Rgb;
+use rendering::Style;
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+
+// the same layout as the png renderers, but walls are stroked lines centered
+// on the cell borders so that they can be cut or scaled without blurring
+
+fn _hex(color: Rgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn _header(width: f32, height: f32, style: &Style) -> String {
+    let mut doc = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height
+    );
+    doc += &format!(
+        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        width,
+        height,
+        _hex(style.background_color)
+    );
+    doc
+}
+
+fn _fill(style: &Style, in_solution: bool, weight: u32, max_weight: u32) -> Option<Rgb<u8>> {
+    if style.draw_solution && in_solution {
+        Some(style.solution_color)
+    } else {
+        style.color_fn.map(|f| f(weight, max_weight))
+    }
+}
+
+fn _line(x1: f32, y1: f32, x2: f32, y2: f32) -> String {
+    format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n", x1, y1, x2, y2)
+}
+
+fn _walls(style: &Style, paths: &[String]) -> String {
+    format!(
+        "<g fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" \
+         stroke-linecap=\"square\">\n{}</g>\n",
+        _hex(style.wall_color),
+        style.wall_thickness.max(1),
+        paths.concat()
+    )
+}
+
+pub fn grid_document(grid: &Grid, style: &Style) -> String {
+    let p = (style.cell_size + style.wall_thickness) as f32;
+    let t = style.wall_thickness as f32;
+    let width = grid.width as f32 * p + t;
+    let height = grid.height as f32 * p + t;
+    let max_weight = grid.cells.iter().map(|c| c.weight()).max().unwrap_or(0);
+
+    let linked = |cell: &Cell, other: &Option<Position>| match *other {
+        Some(ref pos) => cell.is_linked_pos(pos),
+        None => false,
+    };
+
+    let mut doc = _header(width, height, style);
+    let mut walls = Vec::new();
+
+    for cell in &grid.cells {
+        let x1 = cell.pos.col as f32 * p + t / 2.0;
+        let y1 = cell.pos.row as f32 * p + t / 2.0;
+        let x2 = x1 + p;
+        let y2 = y1 + p;
+
+        if let Some(color) = _fill(style, cell.in_solution(), cell.weight(), max_weight) {
+            doc += &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                x1,
+                y1,
+                p,
+                p,
+                _hex(color)
+            );
+        }
+
+        // the first row and column also draw the outer walls, unless they
+        // are open across a wrapped seam
+        if cell.pos.row == 0 && !linked(cell, &cell.north) {
+            walls.push(_line(x1, y1, x2, y1));
+        }
+
+        if cell.pos.col == 0 && !linked(cell, &cell.west) {
+            walls.push(_line(x1, y1, x1, y2));
+        }
+
+        if !linked(cell, &cell.east) {
+            walls.push(_line(x2, y1, x2, y2));
+        }
+
+        if !linked(cell, &cell.south) {
+            walls.push(_line(x1, y2, x2, y2));
+        }
+    }
+
+    doc += &_walls(style, &walls);
+    doc += "</svg>\n";
+    doc
+}
+
+pub fn polar_document(grid: &PolarGrid, style: &Style) -> String {
+    let offset = 5.0;
+    let size = (grid.rows * 2) as f32 * style.cell_size as f32 + offset * 2.0;
+    let center = size / 2.0;
+    let max_weight = grid.cells.iter().map(|c| c.weight()).max().unwrap_or(0);
+
+    let point = |radius: f32, theta: f32| {
+        (
+            center + radius * theta.cos(),
+            center + radius * theta.sin(),
+        )
+    };
+
+    let mut doc = _header(size, size, style);
+    let mut walls = Vec::new();
+
+    for cell in &grid.cells {
+        let pos = cell.pos();
+        let th = 2.0 * PI / grid.column_counts[pos.row] as f32;
+        let inner_radius = (pos.row as u32 * style.cell_size) as f32;
+        let outer_radius = ((pos.row + 1) as u32 * style.cell_size) as f32;
+        let th_ccw = pos.col as f32 * th;
+        let th_cw = (pos.col + 1) as f32 * th;
+        let large = if th > PI { 1 } else { 0 };
+
+        let (ax, ay) = point(inner_radius, th_ccw);
+        let (bx, by) = point(outer_radius, th_ccw);
+        let (cx, cy) = point(inner_radius, th_cw);
+        let (dx, dy) = point(outer_radius, th_cw);
+
+        if let Some(color) = _fill(style, cell.in_solution(), cell.weight(), max_weight) {
+            if pos.row == 0 {
+                doc += &format!(
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                    center,
+                    center,
+                    outer_radius,
+                    _hex(color)
+                );
+            } else {
+                doc += &format!(
+                    "<path d=\"M {} {} L {} {} A {r1} {r1} 0 {l} 1 {} {} \
+                     L {} {} A {r0} {r0} 0 {l} 0 {} {} Z\" fill=\"{}\"/>\n",
+                    ax,
+                    ay,
+                    bx,
+                    by,
+                    dx,
+                    dy,
+                    cx,
+                    cy,
+                    ax,
+                    ay,
+                    _hex(color),
+                    r0 = inner_radius,
+                    r1 = outer_radius,
+                    l = large
+                );
+            }
+        }
+
+        if let Some(ref inward) = cell.inward {
+            if !cell.is_linked_pos(inward) {
+                walls.push(format!(
+                    "<path d=\"M {} {} A {r} {r} 0 {l} 1 {} {}\"/>\n",
+                    ax,
+                    ay,
+                    cx,
+                    cy,
+                    r = inner_radius,
+                    l = large
+                ));
+            }
+        }
+
+        if let Some(ref cw) = cell.cw {
+            if !cell.is_linked_pos(cw) {
+                walls.push(_line(cx, cy, dx, dy));
+            }
+        }
+    }
+
+    walls.push(format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>\n",
+        center,
+        center,
+        (grid.rows as u32 * style.cell_size) as f32
+    ));
+
+    doc += &_walls(style, &walls);
+    doc += "</svg>\n";
+    doc
+}
+
+pub fn svg(grid: &Grid, style: &Style, name: &str) -> io::Result<()> {
+    File::create(name)?.write_all(grid_document(grid, style).as_bytes())
+}
+
+pub fn polar_svg(grid: &PolarGrid, style: &Style, name: &str) -> io::Result<()> {
+    File::create(name)?.write_all(polar_document(grid, style).as_bytes())
+}
+
+#[cfg(test)]
+mod test_svg {
+    use super::*;
+    use data::grid::MazeGrid;
+    use rendering::StyleBuilder;
+
+    #[test]
+    fn grid_walls() {
+        let mut grid = Grid::new(2, 1);
+        let style = StyleBuilder::new().cell_size(8).wall_thickness(2).build();
+
+        // two cells have 7 walls between them
+        let doc = grid_document(&grid, &style);
+        assert!(doc.starts_with("<svg"));
+        assert!(doc.contains("width=\"22\" height=\"12\""));
+        assert_eq!(doc.matches("<line").count(), 7);
+
+        grid.link(&Position::new(0, 0), &Position::new(0, 1));
+        let doc = grid_document(&grid, &style);
+        assert_eq!(doc.matches("<line").count(), 6);
+        assert!(!doc.contains("<rect x=\"1\""));
+    }
+
+    #[test]
+    fn grid_fills() {
+        let mut grid = Grid::new(2, 2);
+        grid.get_mut(&Position::new(1, 1)).unwrap().mark_in_solution();
+        let style = StyleBuilder::new()
+            .color_fn(|_, _| Rgb([1, 2, 3]))
+            .draw_solution()
+            .solution_color(&[255, 0, 0])
+            .build();
+
+        let doc = grid_document(&grid, &style);
+        assert_eq!(doc.matches("fill=\"#010203\"").count(), 3);
+        assert_eq!(doc.matches("fill=\"#ff0000\"").count(), 1);
+    }
+
+    #[test]
+    fn polar_walls_are_arcs() {
+        let grid = PolarGrid::new(3);
+        let style = StyleBuilder::new().build();
+        let doc = polar_document(&grid, &style);
+
+        // every cell outside the center has an inward arc and a cw wall
+        let outer = grid.cells.len() - 1;
+        assert_eq!(doc.matches(" A ").count(), outer);
+        assert_eq!(doc.matches("<line").count(), outer);
+        assert_eq!(doc.matches("<circle").count(), 1);
+    }
+}
@@ -0,0 +1,424 @@
+use algorithms::Algorithm;
+use data::cell::{Cell, MazeCell};
+use data::grid::{Grid, MazeGrid};
+use data::pos::Position;
+use rand::Rng;
+use solver::shortest_path;
+use std::collections::BTreeSet;
+use std::io;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use termion::event::Key;
+use termion::input::TermRead;
+use tui::backend::AlternateScreenBackend;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::widgets::Widget;
+use tui::Terminal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heading {
+    North,
+    South,
+    East,
+    West,
+}
+
+// a maze being played from the top left corner to the bottom right one
+pub struct Game {
+    pub grid: Grid,
+    pub algorithm: Algorithm,
+    pub player: Position,
+    pub goal: Position,
+    pub moves: usize,
+    pub show_solution: bool,
+    started: Instant,
+    finished: Option<Duration>,
+}
+
+impl Game {
+    pub fn new<R: Rng>(
+        width: usize,
+        height: usize,
+        algorithm: Algorithm,
+        rng: &mut R,
+    ) -> io::Result<Self> {
+        // there has to be a cell to start from and one to reach
+        if width == 0 || height == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("can't play a {}x{} maze", width, height),
+            ));
+        }
+
+        let mut grid = Grid::new(width, height);
+        algorithm.generate(&mut grid, rng);
+
+        Ok(Game {
+            grid,
+            algorithm,
+            player: Position::new(0, 0),
+            goal: Position::new(height - 1, width - 1),
+            moves: 0,
+            show_solution: false,
+            started: Instant::now(),
+            finished: None,
+        })
+    }
+
+    // a fresh maze carved into the same grid, keeping the solution toggle
+    pub fn regenerate<R: Rng>(&mut self, rng: &mut R) {
+        self.grid.reset_links();
+        self.algorithm.generate(&mut self.grid, rng);
+        self.player = Position::new(0, 0);
+        self.moves = 0;
+        self.started = Instant::now();
+        self.finished = None;
+    }
+
+    pub fn next_algorithm<R: Rng>(&mut self, rng: &mut R) {
+        self.algorithm = self.algorithm.next();
+        self.regenerate(rng);
+    }
+
+    // moves the player unless there is a wall in the way
+    pub fn step(&mut self, heading: Heading) -> bool {
+        if self.solved() {
+            return false;
+        }
+
+        let target = {
+            let cell = self.grid.get(&self.player).unwrap();
+            match heading {
+                Heading::North => cell.north.clone(),
+                Heading::South => cell.south.clone(),
+                Heading::East => cell.east.clone(),
+                Heading::West => cell.west.clone(),
+            }
+        };
+
+        match target {
+            Some(ref pos) if self.grid.get(&self.player).unwrap().is_linked_pos(pos) => {
+                self.player = pos.clone();
+                self.moves += 1;
+                if self.solved() {
+                    self.finished = Some(self.started.elapsed());
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn solved(&self) -> bool {
+        self.player == self.goal
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    // the path from the player to the goal
+    pub fn solution(&self) -> BTreeSet<Position> {
+        match shortest_path(&self.grid, &self.player, &self.goal) {
+            Some(path) => path.cells().iter().cloned().collect(),
+            None => BTreeSet::new(),
+        }
+    }
+
+    pub fn status(&self) -> String {
+        let elapsed = self.elapsed();
+        let time = format!("{}.{}s", elapsed.as_secs(), elapsed.subsec_millis() / 100);
+        if self.solved() {
+            format!("solved in {} moves and {}", self.moves, time)
+        } else {
+            format!("moves: {}  time: {}  algorithm: {}", self.moves, time, self.algorithm)
+        }
+    }
+}
+
+fn _junction(up: bool, down: bool, left: bool, right: bool) -> char {
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+// draws the maze with box drawing characters, each cell being three
+// characters wide and one tall
+pub fn draw_maze(grid: &Grid) -> Vec<String> {
+    let linked = |cell: &Cell, other: &Option<Position>| match *other {
+        Some(ref pos) => cell.is_linked_pos(pos),
+        None => false,
+    };
+
+    // walls along the top of each cell, including one extra row for the
+    // bottom border, and along the left of each cell plus the right border
+    let horizontal = |row: usize, col: usize| -> bool {
+        if col >= grid.width {
+            return false;
+        }
+        if row < grid.height {
+            let cell = grid.get(&Position::new(row, col)).unwrap();
+            !linked(cell, &cell.north)
+        } else {
+            let cell = grid.get(&Position::new(row - 1, col)).unwrap();
+            !linked(cell, &cell.south)
+        }
+    };
+
+    let vertical = |row: usize, col: usize| -> bool {
+        if row >= grid.height {
+            return false;
+        }
+        if col < grid.width {
+            let cell = grid.get(&Position::new(row, col)).unwrap();
+            !linked(cell, &cell.west)
+        } else {
+            let cell = grid.get(&Position::new(row, col - 1)).unwrap();
+            !linked(cell, &cell.east)
+        }
+    };
+
+    let mut lines = Vec::new();
+    for row in 0..grid.height + 1 {
+        let mut top = String::new();
+        let mut mid = String::new();
+
+        for col in 0..grid.width + 1 {
+            let up = row > 0 && vertical(row - 1, col);
+            let left = col > 0 && horizontal(row, col - 1);
+            top.push(_junction(up, vertical(row, col), left, horizontal(row, col)));
+            mid.push(if vertical(row, col) { '│' } else { ' ' });
+
+            if col < grid.width {
+                let wall = if horizontal(row, col) { "───" } else { "   " };
+                top.push_str(wall);
+                mid.push_str("   ");
+            }
+        }
+
+        lines.push(top);
+        if row < grid.height {
+            lines.push(mid);
+        }
+    }
+
+    lines
+}
+
+struct GameView<'a> {
+    game: &'a Game,
+}
+
+impl<'a> GameView<'a> {
+    fn mark(&self, buf: &mut Buffer, area: &Rect, pos: &Position, ch: char, color: Color) {
+        let x = area.left() + (pos.col * 4 + 2) as u16;
+        let y = area.top() + (pos.row * 2 + 1) as u16;
+        if x < area.right() && y < area.bottom() {
+            buf.get_mut(x, y).set_char(ch).set_fg(color);
+        }
+    }
+}
+
+impl<'a> Widget for GameView<'a> {
+    fn draw(&mut self, area: &Rect, buf: &mut Buffer) {
+        let lines = draw_maze(&self.game.grid);
+        let width = area.width as usize;
+        for (i, line) in lines.iter().enumerate().take(area.height as usize) {
+            buf.set_stringn(area.left(), area.top() + i as u16, line, width, &Style::default());
+        }
+
+        if self.game.show_solution {
+            for pos in self.game.solution() {
+                self.mark(buf, area, &pos, '·', Color::Red);
+            }
+        }
+
+        self.mark(buf, area, &self.game.goal, '◆', Color::Green);
+        self.mark(buf, area, &self.game.player, '@', Color::Yellow);
+
+        let help = "arrows/hjkl move  s solution  r regenerate  a algorithm  q quit";
+        let y = area.top() + lines.len() as u16 + 1;
+        for (i, text) in [self.game.status(), help.to_string()].iter().enumerate() {
+            if y + (i as u16) < area.bottom() {
+                buf.set_stringn(area.left(), y + i as u16, text, width, &Style::default());
+            }
+        }
+    }
+}
+
+enum Input {
+    Key(Key),
+    Tick,
+}
+
+// plays mazes in the terminal until the player quits
+pub fn run<R: Rng>(
+    width: usize,
+    height: usize,
+    algorithm: Algorithm,
+    rng: &mut R,
+) -> io::Result<()> {
+    let mut game = Game::new(width, height, algorithm, rng)?;
+    let mut terminal = Terminal::new(AlternateScreenBackend::new()?)?;
+    terminal.hide_cursor()?;
+    terminal.clear()?;
+
+    // keys come in on their own thread so that the timer keeps ticking
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for key in io::stdin().keys().flatten() {
+            if tx.send(key).is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut size = terminal.size()?;
+
+    loop {
+        let current = terminal.size()?;
+        if current != size {
+            terminal.resize(current)?;
+            size = current;
+        }
+
+        GameView { game: &game }.render(&mut terminal, &size);
+        terminal.draw()?;
+
+        let input = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(key) => Input::Key(key),
+            Err(mpsc::RecvTimeoutError::Timeout) => Input::Tick,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+
+        if let Input::Key(key) = input {
+            match key {
+                Key::Char('q') | Key::Esc | Key::Ctrl('c') => break,
+                Key::Up | Key::Char('k') => {
+                    game.step(Heading::North);
+                }
+                Key::Down | Key::Char('j') => {
+                    game.step(Heading::South);
+                }
+                Key::Left | Key::Char('h') => {
+                    game.step(Heading::West);
+                }
+                Key::Right | Key::Char('l') => {
+                    game.step(Heading::East);
+                }
+                Key::Char('s') => game.show_solution = !game.show_solution,
+                Key::Char('r') => game.regenerate(rng),
+                Key::Char('a') => game.next_algorithm(rng),
+                _ => {}
+            }
+        }
+    }
+
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_terminal {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    fn corridor() -> Game {
+        // a single row with every cell linked
+        let mut game = Game::new(3, 1, Algorithm::Binary, &mut StdRng::from_seed([0; 32])).unwrap();
+        game.grid = Grid::new(3, 1);
+        game.grid.link(&Position::new(0, 0), &Position::new(0, 1));
+        game.grid.link(&Position::new(0, 1), &Position::new(0, 2));
+        game
+    }
+
+    #[test]
+    fn walls_block_movement() {
+        let mut game = corridor();
+
+        assert!(!game.step(Heading::North));
+        assert!(!game.step(Heading::West));
+        assert_eq!(game.moves, 0);
+
+        assert!(game.step(Heading::East));
+        assert!(game.step(Heading::West));
+        assert!(game.step(Heading::East));
+        assert_eq!(game.player, Position::new(0, 1));
+        assert_eq!(game.moves, 3);
+        assert!(!game.solved());
+
+        assert!(game.step(Heading::East));
+        assert!(game.solved());
+        assert!(game.status().starts_with("solved in 4 moves"));
+
+        // nothing moves once the maze is solved
+        assert!(!game.step(Heading::West));
+    }
+
+    #[test]
+    fn solution_from_player() {
+        let mut game = corridor();
+        game.step(Heading::East);
+
+        let expected = [Position::new(0, 1), Position::new(0, 2)];
+        assert_eq!(game.solution(), expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn regenerating() {
+        let mut rng = StdRng::from_seed([1; 32]);
+        let mut game = Game::new(4, 4, Algorithm::Binary, &mut rng).unwrap();
+        game.show_solution = true;
+        game.next_algorithm(&mut rng);
+
+        assert_eq!(game.algorithm, Algorithm::Sidewinder);
+        assert!(game.show_solution);
+        assert_eq!(game.moves, 0);
+
+        let links = game.grid.cells().iter().map(|c| c.links().len()).sum::<usize>();
+        assert_eq!(links, 2 * 15);
+    }
+
+    #[test]
+    fn empty_sizes() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        assert!(Game::new(0, 4, Algorithm::Binary, &mut rng).is_err());
+        assert!(Game::new(4, 0, Algorithm::Binary, &mut rng).is_err());
+        assert!(Game::new(1, 1, Algorithm::Binary, &mut rng).is_ok());
+    }
+
+    #[test]
+    fn drawing() {
+        let mut grid = Grid::new(2, 2);
+        grid.link(&Position::new(0, 0), &Position::new(0, 1));
+        grid.link(&Position::new(0, 1), &Position::new(1, 1));
+        grid.link(&Position::new(1, 1), &Position::new(1, 0));
+
+        let expected = vec![
+            "┌───────┐",
+            "│       │",
+            "├───╴   │",
+            "│       │",
+            "└───────┘",
+        ];
+        assert_eq!(draw_maze(&grid), expected);
+    }
+}